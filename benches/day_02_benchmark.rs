@@ -1,20 +1,21 @@
 ﻿use advent::day_02;
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
 use std::fs::File;
 use std::io;
 
 fn day_02_benchmark(c: &mut Criterion) {
-    let input = "inputs\\day_02.txt";
+    let input = input::input_path(2);
     let file = match File::open(&input) {
         Ok(file) => file,
         Err(e) => {
-            eprintln!("Error: Failed to open file '{}': {}", input, e);
+            eprintln!("Error: Failed to open file '{}': {}", input.display(), e);
             return;
         }
     };
 
     let reader = io::BufReader::new(file);
-    let reports = day_02::parse_reports(reader);
+    let reports = day_02::parse_reports(reader).expect("Error: Failed to parse the day 02 input");
 
     c.bench_function("day_02__count_safe_reports_with_tolerance", |b| {
         b.iter(|| day_02::count_safe_reports_with_tolerance(&reports))
@@ -1,10 +1,9 @@
-﻿use advent::day_03;
+﻿use advent::day_03::{self, Engine};
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
-fn day_06_benchmark_parse(c: &mut Criterion) {
-    let input = "inputs\\day_03.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_03.txt'");
+fn day_03_benchmark_parse(c: &mut Criterion) {
+    let input = input::read_input(3).expect("Error: Failed to read the day 03 input");
 
     let mut group = c.benchmark_group("day_03__parse");
 
@@ -15,11 +14,19 @@ fn day_06_benchmark_parse(c: &mut Criterion) {
     group.bench_function("no_regex", |b| b.iter(|| day_03::sum_instructions(&day_03::parse_instructions_no_regex(&input))));
 
     group.bench_function("no_regex_and_sum", |b| b.iter(|| day_03::parse_instructions_no_regex_and_sum(&input)));
+
+    group.bench_function("memchr_and_sum", |b| b.iter(|| day_03::parse_instructions_memchr_and_sum(&input)));
+
+    group.bench_function("stream", |b| b.iter(|| day_03::sum_instructions_stream(input.as_bytes(), false).unwrap()));
+
+    for (name, engine) in [("grammar_scalar", Engine::Scalar), ("grammar_memchr", Engine::Memchr), ("grammar_regex", Engine::Regex)] {
+        let grammar = day_03::puzzle_grammar(false, engine);
+        group.bench_function(name, |b| b.iter(|| grammar.evaluate(input.as_bytes()).unwrap().total));
+    }
 }
 
-fn day_06_benchmark_parse_with_do(c: &mut Criterion) {
-    let input = "inputs\\day_03.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_03.txt'");
+fn day_03_benchmark_parse_with_do(c: &mut Criterion) {
+    let input = input::read_input(3).expect("Error: Failed to read the day 03 input");
 
     let mut group = c.benchmark_group("day_03__parse_with_do");
 
@@ -30,7 +37,16 @@ fn day_06_benchmark_parse_with_do(c: &mut Criterion) {
     group.bench_function("no_regex", |b| b.iter(|| day_03::sum_instructions(&day_03::parse_instructions_with_do_no_regex(&input))));
 
     group.bench_function("no_regex_and_sum", |b| b.iter(|| day_03::parse_instructions_with_do_no_regex_and_sum(&input)));
+
+    group.bench_function("memchr_and_sum", |b| b.iter(|| day_03::parse_instructions_with_do_memchr_and_sum(&input)));
+
+    group.bench_function("stream", |b| b.iter(|| day_03::sum_instructions_stream(input.as_bytes(), true).unwrap()));
+
+    for (name, engine) in [("grammar_scalar", Engine::Scalar), ("grammar_memchr", Engine::Memchr), ("grammar_regex", Engine::Regex)] {
+        let grammar = day_03::puzzle_grammar(true, engine);
+        group.bench_function(name, |b| b.iter(|| grammar.evaluate(input.as_bytes()).unwrap().total));
+    }
 }
 
-criterion_group!(benches, day_06_benchmark_parse, day_06_benchmark_parse_with_do);
+criterion_group!(benches, day_03_benchmark_parse, day_03_benchmark_parse_with_do);
 criterion_main!(benches);
@@ -1,12 +1,11 @@
 ﻿use advent::day_04;
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
 fn day_06_benchmark_parse(c: &mut Criterion) {
-    let input = "inputs\\day_04.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_04.txt'");
+    let input = input::read_input(4).expect("Error: Failed to read the day 04 input");
 
-    let word_search = day_04::WordSearch::new(input);
+    let word_search = day_04::WordSearch::parse(&input).expect("Error: Failed to parse the day 04 input");
 
     let mut group = c.benchmark_group("day_04__xmas");
 
@@ -16,10 +15,9 @@ fn day_06_benchmark_parse(c: &mut Criterion) {
 }
 
 fn day_06_benchmark_parse_with_do(c: &mut Criterion) {
-    let input = "inputs\\day_04.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_04.txt'");
+    let input = input::read_input(4).expect("Error: Failed to read the day 04 input");
 
-    let word_search = day_04::WordSearch::new(input);
+    let word_search = day_04::WordSearch::parse(&input).expect("Error: Failed to parse the day 04 input");
 
     let mut group = c.benchmark_group("day_04__mas_diagonal");
 
@@ -1,7 +1,8 @@
-﻿use advent::char_map::CharMap;
+﻿use advent::char_map::{ByteMap, CharMap};
 use advent::day_06;
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
+use std::thread;
 
 fn day_06_block_guard(guard_map: &CharMap) {
     let mut guard_map = guard_map.clone();
@@ -14,15 +15,22 @@ fn day_06_block_guard_assign_map(guard_map: &CharMap) {
 }
 
 fn day_06_benchmark(c: &mut Criterion) {
-    let input = "inputs\\day_06.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_06.txt'");
-    let guard_map = CharMap::new(&input);
+    let input = input::read_input(6).expect("Error: Failed to read the day 06 input");
+    let guard_map = CharMap::parse(&input).expect("Error: Failed to parse the day 06 input");
+    let guard_byte_map = ByteMap::parse_ascii(&input).expect("Error: Failed to parse the day 06 input");
 
     let mut group = c.benchmark_group("day_06__block_guard");
 
     group.bench_function("assign_map", |b| b.iter(|| day_06_block_guard_assign_map(&guard_map)));
 
     group.bench_function("original", |b| b.iter(|| day_06_block_guard(&guard_map)));
+
+    group.bench_function("layer", |b| b.iter(|| _ = day_06::block_guard_layer(&guard_byte_map)));
+
+    group.bench_function("jump", |b| b.iter(|| _ = day_06::block_guard_jump(&guard_byte_map)));
+
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    group.bench_function("parallel", |b| b.iter(|| _ = day_06::block_guard_parallel(&guard_byte_map, threads)));
 }
 
 criterion_group!(benches, day_06_benchmark);
@@ -1,20 +1,21 @@
 ﻿use advent::day_07;
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
 use std::fs::File;
 use std::io;
 
 fn day_07_part_one_benchmark(c: &mut Criterion) {
-    let input = "inputs\\day_07.txt";
+    let input = input::input_path(7);
     let file = match File::open(&input) {
         Ok(file) => file,
         Err(e) => {
-            eprintln!("Error: Failed to open file '{}': {}", input, e);
+            eprintln!("Error: Failed to open file '{}': {}", input.display(), e);
             return;
         }
     };
 
     let reader = io::BufReader::new(file);
-    let equations = day_07::parse_equations(reader);
+    let equations = day_07::parse_equations(reader).expect("Error: Failed to parse the day 07 input");
 
     let mut group = c.benchmark_group("day_07__part_one");
 
@@ -22,17 +23,17 @@ fn day_07_part_one_benchmark(c: &mut Criterion) {
 }
 
 fn day_07_part_two_benchmark_part_two(c: &mut Criterion) {
-    let input = "inputs\\day_07.txt";
+    let input = input::input_path(7);
     let file = match File::open(&input) {
         Ok(file) => file,
         Err(e) => {
-            eprintln!("Error: Failed to open file '{}': {}", input, e);
+            eprintln!("Error: Failed to open file '{}': {}", input.display(), e);
             return;
         }
     };
 
     let reader = io::BufReader::new(file);
-    let equations = day_07::parse_equations(reader);
+    let equations = day_07::parse_equations(reader).expect("Error: Failed to parse the day 07 input");
 
     let mut group = c.benchmark_group("day_07__part_two");
 
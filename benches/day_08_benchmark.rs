@@ -1,13 +1,12 @@
 ﻿use advent::char_map::CharMap;
 use advent::day_08;
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
 fn day_08_part_one_benchmark(c: &mut Criterion) {
-    let input = "inputs\\day_08.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_08.txt'");
+    let input = input::read_input(8).expect("Error: Failed to read the day 08 input");
 
-    let antennas_map = CharMap::new(&input);
+    let antennas_map = CharMap::parse(&input).expect("Error: Failed to parse the day 08 input");
     let antennas = day_08::gather_antennas(&antennas_map);
 
     let mut group = c.benchmark_group("day_08__part_one");
@@ -20,10 +19,9 @@ fn day_08_part_one_benchmark(c: &mut Criterion) {
 }
 
 fn day_08_part_two_benchmark_part_two(c: &mut Criterion) {
-    let input = "inputs\\day_08.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_08.txt'");
+    let input = input::read_input(8).expect("Error: Failed to read the day 08 input");
 
-    let antennas_map = CharMap::new(&input);
+    let antennas_map = CharMap::parse(&input).expect("Error: Failed to parse the day 08 input");
     let antennas = day_08::gather_antennas(&antennas_map);
 
     let mut group = c.benchmark_group("day_08__part_two");
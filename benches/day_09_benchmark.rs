@@ -1,10 +1,9 @@
 ﻿use advent::day_09;
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
 fn day_09_part_one_benchmark(c: &mut Criterion) {
-    let input = "inputs\\day_09.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_09.txt'");
+    let input = input::read_input(9).expect("Error: Failed to read the day 09 input");
     let input = input.chars().collect();
 
     let mut group = c.benchmark_group("day_09__part_one");
@@ -13,8 +12,7 @@ fn day_09_part_one_benchmark(c: &mut Criterion) {
 }
 
 fn day_09_part_two_benchmark_part_two(c: &mut Criterion) {
-    let input = "inputs\\day_09.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_09.txt'");
+    let input = input::read_input(9).expect("Error: Failed to read the day 09 input");
     let input = input.chars().collect();
 
     let mut group = c.benchmark_group("day_09__part_two");
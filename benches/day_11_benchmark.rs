@@ -1,11 +1,10 @@
 ﻿use advent::day_11;
+use advent::input;
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::fs;
 
 fn day_11_part_one_benchmark(c: &mut Criterion) {
-    let input = "inputs\\day_11.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_11.txt'");
-    let stones = day_11::parse_stones(&input);
+    let input = input::read_input(11).expect("Error: Failed to read the day 11 input");
+    let stones = day_11::parse_stones(&input).expect("Error: Failed to parse the day 11 input");
 
     let mut group = c.benchmark_group("day_11__part_one");
 
@@ -15,9 +14,8 @@ fn day_11_part_one_benchmark(c: &mut Criterion) {
 }
 
 fn day_11_part_two_benchmark_part_two(c: &mut Criterion) {
-    let input = "inputs\\day_11.txt";
-    let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_11.txt'");
-    let stones = day_11::parse_stones(&input);
+    let input = input::read_input(11).expect("Error: Failed to read the day 11 input");
+    let stones = day_11::parse_stones(&input).expect("Error: Failed to parse the day 11 input");
 
     let mut group = c.benchmark_group("day_11__part_two");
 
@@ -0,0 +1,43 @@
+﻿use advent::char_map::{ByteMap, CharMap};
+use advent::day_12;
+use advent::input;
+use advent::solution::Solution;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn day_12_benchmark(c: &mut Criterion) {
+    let input = input::read_input(12).expect("Error: Failed to read the day 12 input");
+    let garden = CharMap::parse(&input).expect("Error: Failed to parse the day 12 input");
+    let garden_bytes = ByteMap::parse_ascii(&input).expect("Error: Failed to parse the day 12 input");
+
+    let mut group = c.benchmark_group("day_12__fencing_price");
+
+    group.bench_function("char_map", |b| b.iter(|| _ = day_12::calculate_fencing_price(&garden)));
+
+    group.bench_function("byte_map", |b| b.iter(|| _ = day_12::calculate_fencing_price(&garden_bytes)));
+
+    group.bench_function("byte_map_neighbours", |b| b.iter(|| _ = day_12::calculate_fencing_price_neighbours(&garden_bytes)));
+}
+
+/// Both parts as the baseline solved them, with a pass over the `CharMap` for each part, against the solution,
+/// which shares one pass over the `ByteMap` between the parts.
+fn day_12_benchmark_parts(c: &mut Criterion) {
+    let input = input::read_input(12).expect("Error: Failed to read the day 12 input");
+    let garden = CharMap::parse(&input).expect("Error: Failed to parse the day 12 input");
+
+    let mut group = c.benchmark_group("day_12__parts");
+
+    group.bench_function("char_map_two_passes", |b| {
+        b.iter(|| (day_12::calculate_fencing_price(&garden).0, day_12::calculate_fencing_price(&garden).1))
+    });
+
+    group.bench_function("solution", |b| {
+        b.iter(|| {
+            let mut solution = day_12::Day12::default();
+            solution.parse(&input).unwrap();
+            (solution.part_one().value, solution.part_two().value)
+        })
+    });
+}
+
+criterion_group!(benches, day_12_benchmark, day_12_benchmark_parts);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+﻿use crate::error::{parse_number, Error, Result};
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+/// Known answers, keyed by day and part.
+pub type Answers = BTreeMap<(u32, u32), String>;
+
+/// Parses the answers file. Each line contains the day, the part and the answer, separated by whitespace.
+/// Empty lines and lines starting with `#` are ignored.
+pub fn parse_answers<R: BufRead>(reader: R) -> Result<Answers> {
+    let mut answers = Answers::new();
+
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_idx + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        if parts.len() != 3 {
+            return Err(Error::parse(line_number, 1, "expected day, part and answer"));
+        }
+        let day = parse_number(&line, line_number, parts[0])?;
+        let part = parse_number(&line, line_number, parts[1])?;
+        if part != 1 && part != 2 {
+            return Err(Error::parse(line_number, 1, format!("invalid part {}, expected 1 or 2", part)));
+        }
+        if answers.insert((day, part), parts[2].to_string()).is_some() {
+            return Err(Error::parse(line_number, 1, format!("duplicated answer for day {} part {}", day, part)));
+        }
+    }
+
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_answers() {
+        let data = "# Day Part Answer\n1 1 2113135\n1 2 19097157\n\n12  2   851994\n";
+        let answers = parse_answers(Cursor::new(data)).unwrap();
+
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers[&(1, 1)], "2113135");
+        assert_eq!(answers[&(1, 2)], "19097157");
+        assert_eq!(answers[&(12, 2)], "851994");
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        let error = parse_answers(Cursor::new("1 1\n")).unwrap_err();
+        assert_eq!(error.to_string(), "1:1: expected day, part and answer");
+
+        let error = parse_answers(Cursor::new("1 3 42\n")).unwrap_err();
+        assert_eq!(error.to_string(), "1:1: invalid part 3, expected 1 or 2");
+
+        let error = parse_answers(Cursor::new("1 1 42\n1 1 43\n")).unwrap_err();
+        assert_eq!(error.to_string(), "2:1: duplicated answer for day 1 part 1");
+    }
+}
@@ -1,74 +1,15 @@
-﻿pub struct CharMap {
-    map: Vec<char>,
-    pub width: i32,
-    pub height: i32,
-    end_width: i32,
-}
-
-impl CharMap {
-    pub fn new(input: &String) -> CharMap {
-        let pos_n = input.find('\n');
-        let pos_r = input.find('\r');
-        let mut width = 0;
-        let mut end_width = 0;
-        if pos_n.is_some() && pos_r.is_some() {
-            width = std::cmp::min(pos_n.unwrap() as i32, pos_r.unwrap() as i32);
-            end_width = 2;
-        } else if pos_n.is_some() {
-            width = pos_n.unwrap() as i32;
-            end_width = 1;
-        } else if pos_r.is_some() {
-            width = pos_r.unwrap() as i32;
-            end_width = 1;
-        }
-        let height = (input.len() as i32) / (width + end_width);
-        CharMap {
-            map: input.chars().collect(),
-            width,
-            height,
-            end_width,
-        }
-    }
-    pub fn clone(&self) -> CharMap {
-        CharMap {
-            map: self.map.clone(),
-            width: self.width,
-            height: self.height,
-            end_width: self.end_width,
-        }
-    }
-
-    pub fn assign(&mut self, other: &CharMap) {
-        //self.map.clone_from(&other.map);
-        for i in 0..self.map.len() {
-            self.map[i] = other.map[i];
-        }
-        self.width = other.width;
-        self.height = other.height;
-        self.end_width = other.end_width;
-    }
+﻿use crate::grid::Grid;
 
-    #[inline]
-    pub fn get(&self, x: i32, y: i32) -> char {
-        if x < 0 || x >= self.width || y < 0 || y >= self.height {
-            return 0 as char;
-        }
-        return self.map[(y * (self.width + self.end_width) + x) as usize];
-    }
+/// A map of characters, one cell per character of the puzzle input.
+pub type CharMap = Grid<char>;
 
-    #[inline]
-    pub fn set(&mut self, x: i32, y: i32, value: char) {
-        if x < 0 || x >= self.width || y < 0 || y >= self.height {
-            return;
-        }
-        let pos = (y * (self.width + self.end_width) + x) as usize;
-        self.map[pos] = value;
-    }
-}
+/// A compact map of ASCII characters, one byte per cell. Use `Grid::layer` for per-cell flags.
+pub type ByteMap = Grid<u8>;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::point::Point;
 
     const INPUT: &str = r#"..#...
 .....#
@@ -79,39 +20,67 @@ mod tests {
 
     #[test]
     fn test_get_size() {
-        let guard_map = CharMap::new(&INPUT.to_string());
-        assert_eq!(guard_map.width, 6);
-        assert_eq!(guard_map.height, 5);
+        let guard_map = CharMap::parse(INPUT).unwrap();
+        assert_eq!(guard_map.width(), 6);
+        assert_eq!(guard_map.height(), 5);
+    }
+
+    #[test]
+    fn test_get_size_line_endings() {
+        let guard_map = CharMap::parse(INPUT).unwrap();
+        assert_eq!(CharMap::parse(INPUT.trim_end()).unwrap(), guard_map);
+        assert_eq!(CharMap::parse(&INPUT.replace('\n', "\r\n")).unwrap(), guard_map);
+        assert_eq!(CharMap::parse(&INPUT.replace('\n', "\r")).unwrap(), guard_map);
+        assert_eq!(CharMap::parse(&format!("{}\n\n", INPUT)).unwrap(), guard_map);
+    }
+
+    #[test]
+    fn test_parse_ragged() {
+        let error = CharMap::parse("..#...\n.....\n..^...\n").unwrap_err();
+        assert_eq!(error.to_string(), "2:6: expected 6 cells, found 5");
+
+        let error = CharMap::parse("..#...\n......\n..^....").unwrap_err();
+        assert_eq!(error.to_string(), "3:7: expected 6 cells, found 7");
+    }
+
+    #[test]
+    fn test_parse_unicode() {
+        let map = CharMap::parse("ab€\n€cd\n").unwrap();
+        assert_eq!(map.width(), 3);
+        assert_eq!(map.height(), 2);
+        assert_eq!(map.get(Point::new(2, 0)), Some(&'€'));
+        assert_eq!(map.get(Point::new(0, 1)), Some(&'€'));
+        assert_eq!(map.get(Point::new(2, 1)), Some(&'d'));
     }
 
     #[test]
     fn test_get() {
-        let guard_map = CharMap::new(&INPUT.to_string());
-        assert_eq!(guard_map.get(0, 0), '.');
-        assert_eq!(guard_map.get(2, 0), '#');
-        assert_eq!(guard_map.get(5, 0), '.');
-        assert_eq!(guard_map.get(0, 3), '#');
-        assert_eq!(guard_map.get(5, 4), '.');
+        let guard_map = CharMap::parse(INPUT).unwrap();
+        assert_eq!(guard_map.get_or(Point::new(0, 0), '\0'), '.');
+        assert_eq!(guard_map.get_or(Point::new(2, 0), '\0'), '#');
+        assert_eq!(guard_map.get_or(Point::new(5, 0), '\0'), '.');
+        assert_eq!(guard_map.get_or(Point::new(0, 3), '\0'), '#');
+        assert_eq!(guard_map.get_or(Point::new(5, 4), '\0'), '.');
     }
 
     #[test]
     fn test_get_outside_bounds() {
-        let guard_map = CharMap::new(&INPUT.to_string());
-        assert_eq!(guard_map.get(6, 0), 0 as char);
-        assert_eq!(guard_map.get(0, 5), 0 as char);
-        assert_eq!(guard_map.get(6, 5), 0 as char);
+        let guard_map = CharMap::parse(INPUT).unwrap();
+        assert_eq!(guard_map.get_or(Point::new(6, 0), '\0'), 0 as char);
+        assert_eq!(guard_map.get_or(Point::new(0, 5), '\0'), 0 as char);
+        assert_eq!(guard_map.get_or(Point::new(6, 5), '\0'), 0 as char);
 
-        assert_eq!(guard_map.get(-1, 0), 0 as char);
-        assert_eq!(guard_map.get(0, -10), 0 as char);
-        assert_eq!(guard_map.get(-10, -1), 0 as char);
+        assert_eq!(guard_map.get_or(Point::new(-1, 0), '\0'), 0 as char);
+        assert_eq!(guard_map.get_or(Point::new(0, -10), '\0'), 0 as char);
+        assert_eq!(guard_map.get_or(Point::new(-10, -1), '\0'), 0 as char);
     }
 
     #[test]
     fn test_set() {
-        let mut guard_map = CharMap::new(&INPUT.to_string());
-        guard_map.set(0, 0, 'X');
-        assert_eq!(guard_map.get(0, 0), 'X');
-        guard_map.set(2, 2, 'X');
-        assert_eq!(guard_map.get(2, 2), 'X');
+        let mut guard_map = CharMap::parse(INPUT).unwrap();
+        guard_map.set(Point::new(0, 0), 'X');
+        assert_eq!(guard_map.get_or(Point::new(0, 0), '\0'), 'X');
+        guard_map.set(Point::new(2, 2), 'X');
+        assert_eq!(guard_map.get_or(Point::new(2, 2), '\0'), 'X');
     }
 }
@@ -1,4 +1,6 @@
-﻿use std::io::BufRead;
+﻿use crate::error::{parse_number, Error, Result};
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
 pub fn calculate_distance(a: i32, b: i32) -> i32 {
     (a - b).abs()
@@ -58,26 +60,53 @@ pub fn sum_similarities(array1: &[i32], array2: &[i32]) -> i32 {
     total_similarity
 }
 
-pub fn parse_numbers<R: BufRead>(reader: R) -> (Vec<i32>, Vec<i32>) {
+pub fn parse_numbers<R: BufRead>(reader: R) -> Result<(Vec<i32>, Vec<i32>)> {
     let mut array1 = Vec::new();
     let mut array2 = Vec::new();
 
     // Iterate over each line in the reader
-    for line in reader.lines() {
-        if let Ok(line_content) = line {
-            // Split the line into parts and parse the numbers
-            let parts: Vec<&str> = line_content.split_whitespace().collect();
-            if parts.len() == 2 {
-                if let (Ok(first), Ok(second)) = (parts[0].parse::<i32>(), parts[1].parse::<i32>()) {
-                    // Push the numbers into their respective arrays
-                    array1.push(first);
-                    array2.push(second);
-                }
-            }
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line_content = line?;
+        let line_number = line_idx + 1;
+        // Split the line into parts and parse the numbers
+        let parts: Vec<&str> = line_content.split_whitespace().collect();
+        if parts.is_empty() {
+            continue;
+        }
+        if parts.len() != 2 {
+            return Err(Error::parse(line_number, 1, format!("expected 2 numbers, found {}", parts.len())));
         }
+        // Push the numbers into their respective arrays
+        array1.push(parse_number(&line_content, line_number, parts[0])?);
+        array2.push(parse_number(&line_content, line_number, parts[1])?);
+    }
+
+    Ok((array1, array2))
+}
+
+#[derive(Default)]
+pub struct Day01 {
+    array1: Vec<i32>,
+    array2: Vec<i32>,
+}
+
+impl Solution for Day01 {
+    fn day(&self) -> u32 {
+        1
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        (self.array1, self.array2) = parse_numbers(input.as_bytes())?;
+        Ok(())
     }
 
-    (array1, array2)
+    fn part_one(&self) -> Answer {
+        Answer::new("The total distance is {}", sum_distances(&self.array1, &self.array2))
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new("The similarity score is {}", sum_similarities(&self.array1, &self.array2))
+    }
 }
 
 // Test the function
@@ -162,9 +191,26 @@ mod tests {
     fn test_parse_numbers() {
         let data = "1 2\n3 4\n5 6\n";
         let cursor = Cursor::new(data);
-        let (first_numbers, second_numbers) = parse_numbers(cursor);
+        let (first_numbers, second_numbers) = parse_numbers(cursor).unwrap();
 
         assert_eq!(first_numbers, vec![1, 3, 5]);
         assert_eq!(second_numbers, vec![2, 4, 6]);
     }
+
+    #[test]
+    fn test_parse_numbers_errors() {
+        let error = parse_numbers(Cursor::new("1 2\n3 x\n")).unwrap_err();
+        assert_eq!(error.to_string(), "2:3: invalid number 'x'");
+
+        let error = parse_numbers(Cursor::new("1 2\n3 4 5\n")).unwrap_err();
+        assert_eq!(error.to_string(), "2:1: expected 2 numbers, found 3");
+    }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day01::default();
+        solution.parse("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n").unwrap();
+        assert_eq!(solution.part_one().value, "11");
+        assert_eq!(solution.part_two().value, "31");
+    }
 }
@@ -1,16 +1,23 @@
-﻿use std::io::BufRead;
+﻿use crate::error::{parse_number, Result};
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
-pub fn parse_reports<R: BufRead>(reader: R) -> Vec<Vec<i32>> {
+pub fn parse_reports<R: BufRead>(reader: R) -> Result<Vec<Vec<i32>>> {
     let mut reports: Vec<Vec<i32>> = Vec::new();
 
-    for line in reader.lines() {
-        if let Ok(line_str) = line {
-            let numbers: Vec<i32> = line_str.split_whitespace().filter_map(|s| s.parse::<i32>().ok()).collect();
-            reports.push(numbers);
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line_str = line?;
+        if line_str.trim().is_empty() {
+            continue;
         }
+        let numbers = line_str
+            .split_whitespace()
+            .map(|s| parse_number(&line_str, line_idx + 1, s))
+            .collect::<Result<Vec<i32>>>()?;
+        reports.push(numbers);
     }
 
-    return reports;
+    return Ok(reports);
 }
 
 fn check_diff(report: &Vec<i32>, first: usize, second: usize) -> bool {
@@ -126,6 +133,30 @@ pub fn count_safe_reports_with_tolerance(reports: &Vec<Vec<i32>>) -> usize {
     reports.iter().filter(|r| is_safe_report_with_tolerance(r)).count()
 }
 
+#[derive(Default)]
+pub struct Day02 {
+    reports: Vec<Vec<i32>>,
+}
+
+impl Solution for Day02 {
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.reports = parse_reports(input.as_bytes())?;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The number of safe reports is {}", count_safe_reports(&self.reports))
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new("The number of safe reports with tolerance is {}", count_safe_reports_with_tolerance(&self.reports))
+    }
+}
+
 // Test the function
 #[cfg(test)]
 mod tests {
@@ -136,13 +167,19 @@ mod tests {
     fn test_parse_reports() {
         let input = "1 2 3\n4 5 6\n7 8 9";
         let reader = Cursor::new(input);
-        let reports = parse_reports(reader);
+        let reports = parse_reports(reader).unwrap();
         assert_eq!(reports.len(), 3);
         assert_eq!(reports[0], vec![1, 2, 3]);
         assert_eq!(reports[1], vec![4, 5, 6]);
         assert_eq!(reports[2], vec![7, 8, 9]);
     }
 
+    #[test]
+    fn test_parse_reports_errors() {
+        let error = parse_reports(Cursor::new("1 2 3\n4 5 -\n")).unwrap_err();
+        assert_eq!(error.to_string(), "2:5: invalid number '-'");
+    }
+
     #[test]
     fn test_is_safe_report() {
         assert_eq!(is_safe_report(&vec![1]), true);
@@ -180,4 +217,12 @@ mod tests {
         assert_eq!(is_safe_report_with_tolerance(&vec![1, 3, 1, 4, 5]), true); // Safe by removing the third level, 1.
         assert_eq!(is_safe_report_with_tolerance(&vec![3, 6, 4, 2, 1]), true); // Safe by removing the first level, 5.
     }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day02::default();
+        solution.parse("7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9\n").unwrap();
+        assert_eq!(solution.part_one().value, "2");
+        assert_eq!(solution.part_two().value, "4");
+    }
 }
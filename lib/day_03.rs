@@ -1,194 +1,655 @@
-﻿use regex::Regex;
+﻿use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use regex::bytes::Regex;
 use std::cmp;
+use std::fmt;
+use std::io::{self, ErrorKind, Read};
+use std::iter;
+use std::ops::Range;
+use std::sync::OnceLock;
 
-pub fn parse_instructions(input: &str) -> Vec<(i32, i32)> {
-    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    let mut results: Vec<(i32, i32)> = Vec::new();
-    for captures in re.captures_iter(input) {
-        if let (Some(a), Some(b)) = (captures.get(1), captures.get(2)) {
-            if let (Ok(a), Ok(b)) = (a.as_str().parse::<i32>(), b.as_str().parse::<i32>()) {
-                results.push((a, b));
-            }
+/// Adds the product to the total, failing instead of wrapping around.
+fn add_product(total: i64, a: i32, b: i32) -> Result<i64> {
+    total.checked_add(a as i64 * b as i64).ok_or(Error::Overflow("the sum of the multiplications"))
+}
+
+/// An instruction found in the corrupted memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(i32, i32),
+    Do,
+    Dont,
+}
+
+impl Instruction {
+    /// Converts a call found with the grammar by the name of its operation, `None` for the operations of other puzzles.
+    fn from_call(grammar: &Grammar, call: &Call) -> Option<Instruction> {
+        match (grammar.operation(call.operation).name.as_str(), call.args()) {
+            ("mul", &[a, b]) => Some(Instruction::Mul(a, b)),
+            ("do", []) => Some(Instruction::Do),
+            ("don't", []) => Some(Instruction::Dont),
+            _ => None,
         }
     }
-    return results;
 }
 
-pub fn parse_instructions_and_sum(input: &str) -> i32 {
-    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    let mut total = 0;
-    for captures in re.captures_iter(input) {
-        if let (Some(a), Some(b)) = (captures.get(1), captures.get(2)) {
-            if let (Ok(a), Ok(b)) = (a.as_str().parse::<i32>(), b.as_str().parse::<i32>()) {
-                total += a * b;
-            }
+/// State changed by the actions of the instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Evaluation {
+    pub total: i64,
+    /// Whether the instructions changing the total are enabled.
+    pub enabled: bool,
+}
+
+impl Default for Evaluation {
+    fn default() -> Evaluation {
+        Evaluation { total: 0, enabled: true }
+    }
+}
+
+/// Applies a call to the evaluation, with the arguments of the call.
+pub type Action = fn(&mut Evaluation, &[i32]) -> Result<()>;
+
+/// The most arguments an operation can take, so the calls keep them without allocating.
+pub const MAX_ARITY: usize = 4;
+
+/// An instruction of the grammar, written as `name(arg,...)` with `arity` arguments of 1 to `max_digits` digits.
+pub struct Operation {
+    pub name: String,
+    pub arity: usize,
+    pub max_digits: u32,
+    action: Action,
+}
+
+/// An instruction found in the memory, the index of its operation in the grammar and its arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Call {
+    pub operation: usize,
+    /// The arguments, followed by zeros up to `MAX_ARITY`.
+    args: [i32; MAX_ARITY],
+    arity: usize,
+}
+
+impl Call {
+    pub fn args(&self) -> &[i32] {
+        &self.args[..self.arity]
+    }
+}
+
+/// Why an instruction was rejected after its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// Another byte was found instead of the given one.
+    Expected(u8),
+    /// An argument has no digits.
+    MissingDigits,
+    /// An argument has more digits than the operation allows.
+    TooManyDigits,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Expected(byte) => write!(f, "expected '{}'", *byte as char),
+            Rejection::MissingDigits => write!(f, "expected a number"),
+            Rejection::TooManyDigits => write!(f, "too many digits"),
         }
     }
-    return total;
 }
 
-pub fn parse_instructions_no_regex(input: &str) -> Vec<(i32, i32)> {
-    let mut results: Vec<(i32, i32)> = Vec::new();
-    let mut i = 0;
-    let bytes = input.as_bytes();
-    while i < input.len() - 4 {
-        if bytes[i] == b'm' && bytes[i + 1] == b'u' && bytes[i + 2] == b'l' && bytes[i + 3] == b'(' {
-            i += 4;
-            // Get first number.
-            let mut a = 0;
-            let max_digits = cmp::min(3, input.len() - i);
-            for _ in 0..max_digits {
-                let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
-                if is_digit {
-                    a = a * 10 + (c - b'0') as i32;
-                    i += 1;
-                } else {
-                    break;
+/// An item of the memory, with the span of its bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// A recognized instruction, `enabled` is the state of the evaluation before its action.
+    Call { call: Call, span: Range<usize>, enabled: bool },
+    /// An instruction rejected after its name. The span ends with the unexpected byte.
+    NearMiss { operation: usize, span: Range<usize>, rejection: Rejection },
+}
+
+impl Token {
+    pub fn span(&self) -> &Range<usize> {
+        match self {
+            Token::Call { span, .. } | Token::NearMiss { span, .. } => span,
+        }
+    }
+}
+
+/// An instruction whose name was found, but not the rest, with the number of bytes reached including the unexpected one.
+#[derive(Debug, Clone, Copy)]
+struct Rejected {
+    reason: Rejection,
+    reached: usize,
+}
+
+/// Result of matching one operation at the start of the bytes.
+enum Attempt {
+    /// The arguments and the length of the call.
+    Matched([i32; MAX_ARITY], usize),
+    Mismatched,
+    Rejected(Rejected),
+    /// The bytes end before it can be decided.
+    Incomplete,
+}
+
+/// Result of matching all the operations at the start of the bytes.
+enum Match {
+    Found(Call, usize),
+    NotFound,
+    /// The index of the operation which got the furthest, and its rejection.
+    Rejected(usize, Rejected),
+    Incomplete,
+}
+
+/// What `Grammar::scan` makes of the instructions rejected after their name.
+trait NearMisses: Sized {
+    /// Returns the near-miss to report for the rejection of the operation, or `None` to skip it.
+    fn near_miss(operation: usize, rejected: Rejected, span: Range<usize>) -> Option<Self>;
+}
+
+/// Skips the rejected instructions, so a scan with it never reports one.
+enum Skip {}
+
+impl NearMisses for Skip {
+    fn near_miss(_: usize, _: Rejected, _: Range<usize>) -> Option<Skip> {
+        None
+    }
+}
+
+/// A rejected instruction, reported as `Token::NearMiss`.
+struct NearMiss {
+    operation: usize,
+    rejection: Rejection,
+    span: Range<usize>,
+}
+
+impl NearMisses for NearMiss {
+    fn near_miss(operation: usize, rejected: Rejected, span: Range<usize>) -> Option<NearMiss> {
+        Some(NearMiss {
+            operation,
+            rejection: rejected.reason,
+            span,
+        })
+    }
+}
+
+/// Result of looking for the next instruction in a part of the memory.
+enum Scan<N> {
+    /// The call and its span.
+    Found(Call, Range<usize>),
+    Rejected(N),
+    /// No call in the bytes, only the given number of bytes can be skipped, the rest may be the start of one.
+    NeedMore(usize),
+}
+
+/// How `Grammar::calls` looks for the instructions. The streaming and the tokens always scan the bytes,
+/// as a regular expression can neither continue across the reads nor report the near-misses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Engine {
+    /// Checks at each byte whether an operation starts with it.
+    Scalar,
+    /// Jumps between the bytes starting the operations with `memchr`, which uses the vector instructions of the CPU
+    /// when available. It searches for up to three different bytes, with more the bytes are checked one at a time.
+    #[default]
+    Memchr,
+    /// Translates the operations to a regular expression with an alternative for each.
+    /// Its scans of the bytes jump with `memchr`.
+    Regex,
+}
+
+/// Table of the instructions to recognize. When several operations match at the same position,
+/// the one registered first wins, and the search continues after the matched instruction.
+pub struct Grammar {
+    operations: Vec<Operation>,
+    /// Whether an operation starts with the byte.
+    starts: Vec<bool>,
+    /// The different bytes starting the operations.
+    start_bytes: Vec<u8>,
+    engine: Engine,
+    /// The regular expression of the operations and the index of the group of each, built on first use.
+    regex: OnceLock<(Regex, Vec<usize>)>,
+}
+
+/// Returns the number written by the ASCII digits.
+fn parse_digits(digits: &[u8]) -> i32 {
+    digits.iter().fold(0, |value, &digit| value * 10 + (digit - b'0') as i32)
+}
+
+impl Grammar {
+    pub fn new() -> Grammar {
+        Grammar {
+            operations: Vec::new(),
+            starts: vec![false; 256],
+            start_bytes: Vec::new(),
+            engine: Engine::default(),
+            regex: OnceLock::new(),
+        }
+    }
+
+    pub fn set_engine(&mut self, engine: Engine) -> &mut Grammar {
+        self.engine = engine;
+        self
+    }
+
+    /// Adds an operation with the action applied for each of its calls. The name has to be non-empty ASCII,
+    /// the arity at most `MAX_ARITY` and, when the operation takes arguments, `max_digits` between 1 and 9,
+    /// so the arguments fit in `i32`. Without arguments `max_digits` is ignored.
+    pub fn register(&mut self, name: &str, arity: usize, max_digits: u32, action: Action) -> Result<&mut Grammar> {
+        if name.is_empty() || !name.is_ascii() {
+            return Err(Error::Invalid(format!("invalid operation name '{}'", name)));
+        }
+        if arity > MAX_ARITY {
+            return Err(Error::Invalid(format!("invalid arity {} for '{}', expected at most {}", arity, name, MAX_ARITY)));
+        }
+        if arity > 0 && !(1..=9).contains(&max_digits) {
+            return Err(Error::Invalid(format!("invalid number of digits {} for '{}', expected 1 to 9", max_digits, name)));
+        }
+        let start = name.as_bytes()[0];
+        if !self.starts[start as usize] {
+            self.starts[start as usize] = true;
+            self.start_bytes.push(start);
+        }
+        self.operations.push(Operation {
+            name: name.to_string(),
+            arity,
+            max_digits,
+            action,
+        });
+        self.regex = OnceLock::new();
+        Ok(self)
+    }
+
+    /// The grammar of the first part: `mul(a,b)` adds the product to the total.
+    pub fn multiplications() -> Grammar {
+        let mut grammar = Grammar::new();
+        grammar
+            .register("mul", 2, 3, |evaluation, args| {
+                if evaluation.enabled {
+                    evaluation.total = add_product(evaluation.total, args[0], args[1])?;
                 }
+                Ok(())
+            })
+            .unwrap();
+        grammar
+    }
+
+    /// The grammar of the second part: `mul(a,b)`, with `do()` and `don't()` enabling and disabling it.
+    pub fn conditional_multiplications() -> Grammar {
+        let mut grammar = Grammar::multiplications();
+        grammar
+            .register("do", 0, 0, |evaluation, _| {
+                evaluation.enabled = true;
+                Ok(())
+            })
+            .unwrap();
+        grammar
+            .register("don't", 0, 0, |evaluation, _| {
+                evaluation.enabled = false;
+                Ok(())
+            })
+            .unwrap();
+        grammar
+    }
+
+    pub fn operation(&self, index: usize) -> &Operation {
+        &self.operations[index]
+    }
+
+    /// Applies the action of the call, failing when the action does, e.g. when the total overflows.
+    pub fn apply(&self, evaluation: &mut Evaluation, call: &Call) -> Result<()> {
+        (self.operations[call.operation].action)(evaluation, call.args())
+    }
+
+    /// Matches the operation at the start of the bytes.
+    fn match_operation(operation: &Operation, bytes: &[u8]) -> Attempt {
+        let name = operation.name.as_bytes();
+        match bytes.get(..name.len()) {
+            None if name.starts_with(bytes) => return Attempt::Incomplete,
+            Some(prefix) if prefix == name => {}
+            _ => return Attempt::Mismatched,
+        }
+        let mut args = [0; MAX_ARITY];
+        let mut i = name.len();
+        let rejected = |i: usize, reason| Attempt::Rejected(Rejected { reason, reached: i + 1 });
+        match bytes.get(i) {
+            None => return Attempt::Incomplete,
+            Some(b'(') => i += 1,
+            Some(_) => return rejected(i, Rejection::Expected(b'(')),
+        }
+        if operation.arity == 0 {
+            return match bytes.get(i) {
+                None => Attempt::Incomplete,
+                Some(b')') => Attempt::Matched(args, i + 1),
+                Some(_) => rejected(i, Rejection::Expected(b')')),
+            };
+        }
+
+        for (arg, value) in args[..operation.arity].iter_mut().enumerate() {
+            let mut digits = 0;
+            while digits < operation.max_digits {
+                match bytes.get(i) {
+                    Some(&byte) if byte.is_ascii_digit() => *value = *value * 10 + (byte - b'0') as i32,
+                    _ => break,
+                }
+                digits += 1;
+                i += 1;
             }
-            if a == 0 {
-                continue;
-            }
-            // Get comma.
-            if i >= input.len() || bytes[i] != b',' {
-                continue;
+            let separator = if arg + 1 < operation.arity { b',' } else { b')' };
+            match bytes.get(i) {
+                None => return Attempt::Incomplete,
+                Some(_) if digits == 0 => return rejected(i, Rejection::MissingDigits),
+                Some(byte) if byte.is_ascii_digit() => return rejected(i, Rejection::TooManyDigits),
+                Some(&byte) if byte != separator => return rejected(i, Rejection::Expected(separator)),
+                Some(_) => i += 1,
             }
-            i += 1;
-            // Get second number.
-            let mut b = 0;
-            let max_digits = cmp::min(3, input.len() - i);
-            for _ in 0..max_digits {
-                let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
-                if is_digit {
-                    b = b * 10 + (c - b'0') as i32;
-                    i += 1;
-                } else {
-                    break;
+        }
+        Attempt::Matched(args, i)
+    }
+
+    /// Matches the operations at the start of the bytes, in the order they were registered.
+    /// When none matches, the rejection which got the furthest is returned, the first one of those on a tie.
+    fn match_at(&self, bytes: &[u8]) -> Match {
+        let mut incomplete = false;
+        let mut rejected: Option<(usize, Rejected)> = None;
+        for (index, operation) in self.operations.iter().enumerate() {
+            match Grammar::match_operation(operation, bytes) {
+                // A longer input could still match an earlier operation, which would win.
+                Attempt::Matched(..) if incomplete => return Match::Incomplete,
+                Attempt::Matched(args, length) => {
+                    return Match::Found(
+                        Call {
+                            operation: index,
+                            args,
+                            arity: operation.arity,
+                        },
+                        length,
+                    )
                 }
+                Attempt::Incomplete => incomplete = true,
+                Attempt::Rejected(rejection) if rejected.is_none_or(|(_, longest)| rejection.reached > longest.reached) => rejected = Some((index, rejection)),
+                Attempt::Rejected(_) | Attempt::Mismatched => {}
             }
-            if b == 0 {
-                continue;
-            }
-            // Get closing parenthesis.
-            if i >= input.len() || bytes[i] != b')' {
-                continue;
+        }
+        match rejected {
+            _ if incomplete => Match::Incomplete,
+            Some((operation, rejection)) => Match::Rejected(operation, rejection),
+            None => Match::NotFound,
+        }
+    }
+
+    /// Returns the offset of the first byte which may start an operation.
+    fn find_start(&self, bytes: &[u8]) -> Option<usize> {
+        match (self.engine, &self.start_bytes[..]) {
+            (Engine::Scalar, _) => bytes.iter().position(|&byte| self.starts[byte as usize]),
+            (_, []) => None,
+            (_, &[a]) => memchr::memchr(a, bytes),
+            (_, &[a, b]) => memchr::memchr2(a, b, bytes),
+            (_, &[a, b, c]) => memchr::memchr3(a, b, c, bytes),
+            _ => bytes.iter().position(|&byte| self.starts[byte as usize]),
+        }
+    }
+
+    /// Looks for the first call in the bytes, or the first rejected instruction reported as a near-miss.
+    /// Unless `at_end` is set, more bytes may follow.
+    fn scan<N: NearMisses>(&self, bytes: &[u8], at_end: bool) -> Scan<N> {
+        let mut i = 0;
+        while let Some(offset) = self.find_start(&bytes[i..]) {
+            i += offset;
+            match self.match_at(&bytes[i..]) {
+                Match::Found(call, length) => return Scan::Found(call, i..i + length),
+                Match::Rejected(operation, rejected) => match N::near_miss(operation, rejected, i..i + rejected.reached) {
+                    Some(near_miss) => return Scan::Rejected(near_miss),
+                    None => i += 1,
+                },
+                Match::Incomplete if !at_end => return Scan::NeedMore(i),
+                Match::Incomplete | Match::NotFound => i += 1,
             }
-            i += 1;
-            // Save result.
-            results.push((a, b));
-        } else {
-            i += 1;
         }
+        Scan::NeedMore(bytes.len())
     }
-    return results;
-}
 
-pub fn parse_instructions_no_regex_and_sum(input: &str) -> i32 {
-    let mut total = 0;
-    let mut i = 0;
-    let bytes = input.as_bytes();
-    while i < input.len() - 4 {
-        if bytes[i] == b'm' && bytes[i + 1] == b'u' && bytes[i + 2] == b'l' && bytes[i + 3] == b'(' {
-            i += 4;
-            // Get first number.
-            let mut a = 0;
-            let max_digits = cmp::min(3, input.len() - i);
-            for _ in 0..max_digits {
-                let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
-                if is_digit {
-                    a = a * 10 + (c - b'0') as i32;
-                    i += 1;
-                } else {
-                    break;
-                }
+    /// Translates the operations to alternatives of a regular expression, in the order they were registered, returning it
+    /// with the index of the group of each alternative. The groups of the arguments follow the group of their operation.
+    fn build_regex(&self) -> (Regex, Vec<usize>) {
+        let mut groups = Vec::with_capacity(self.operations.len());
+        let mut group = 1;
+        let mut alternatives = Vec::with_capacity(self.operations.len());
+        for operation in &self.operations {
+            groups.push(group);
+            group += 1 + operation.arity;
+            let args = vec![format!("([0-9]{{1,{}}})", operation.max_digits); operation.arity];
+            alternatives.push(format!(r"({}\({}\))", regex::escape(&operation.name), args.join(",")));
+        }
+        (Regex::new(&alternatives.join("|")).unwrap(), groups)
+    }
+
+    fn regex_calls<'a>(&'a self, memory: &'a [u8]) -> impl Iterator<Item = Call> + 'a {
+        let (regex, groups) = self.regex.get_or_init(|| self.build_regex());
+        regex.captures_iter(memory).map(move |captures| {
+            let operation = groups.iter().position(|&group| captures.get(group).is_some()).unwrap();
+            let group = groups[operation];
+            let arity = self.operations[operation].arity;
+            let mut args = [0; MAX_ARITY];
+            for (arg, value) in args[..arity].iter_mut().enumerate() {
+                *value = parse_digits(&captures[group + 1 + arg]);
             }
-            if a == 0 {
-                continue;
+            Call { operation, args, arity }
+        })
+    }
+
+    /// Returns the calls in the memory, looking for them with the engine of the grammar.
+    pub fn calls<'a>(&'a self, memory: &'a [u8]) -> Box<dyn Iterator<Item = Call> + 'a> {
+        // Without operations the regular expression would be empty and match everywhere.
+        if self.engine == Engine::Regex && !self.operations.is_empty() {
+            return Box::new(self.regex_calls(memory));
+        }
+        let mut start = 0;
+        Box::new(iter::from_fn(move || match self.scan::<Skip>(&memory[start..], true) {
+            Scan::Found(call, span) => {
+                start += span.end;
+                Some(call)
             }
-            // Get comma.
-            if i >= input.len() || bytes[i] != b',' {
-                continue;
+            Scan::NeedMore(_) => None,
+        }))
+    }
+
+    /// Returns the calls in the memory with their spans and enabled states, and with `near_misses`
+    /// the rejected instructions. As with the calls, the search continues after a near-miss's first byte.
+    pub fn tokens<'a>(&'a self, memory: &'a [u8], near_misses: bool) -> impl Iterator<Item = Token> + 'a {
+        let mut start = 0;
+        let mut evaluation = Evaluation::default();
+        iter::from_fn(move || loop {
+            match self.scan::<NearMiss>(&memory[start..], true) {
+                Scan::Found(call, span) => {
+                    let span = start + span.start..start + span.end;
+                    start = span.end;
+                    let enabled = evaluation.enabled;
+                    // Only the enabled state is reported, so an overflowing total is not an error here.
+                    let _ = self.apply(&mut evaluation, &call);
+                    return Some(Token::Call { call, span, enabled });
+                }
+                Scan::Rejected(NearMiss { operation, rejection, span }) => {
+                    let span = start + span.start..start + span.end;
+                    start = span.start + 1;
+                    if near_misses {
+                        return Some(Token::NearMiss { operation, span, rejection });
+                    }
+                }
+                Scan::NeedMore(_) => return None,
             }
-            i += 1;
-            // Get second number.
-            let mut b = 0;
-            let max_digits = cmp::min(3, input.len() - i);
-            for _ in 0..max_digits {
-                let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
-                if is_digit {
-                    b = b * 10 + (c - b'0') as i32;
-                    i += 1;
-                } else {
-                    break;
+        })
+    }
+
+    /// Returns the calls read from the reader, keeping only a fixed size buffer in memory.
+    pub fn read_calls<R: Read>(&self, reader: R) -> Calls<'_, R> {
+        Calls {
+            grammar: self,
+            reader,
+            buffer: Vec::new(),
+            start: 0,
+            done: false,
+        }
+    }
+
+    pub fn evaluate(&self, memory: &[u8]) -> Result<Evaluation> {
+        let mut evaluation = Evaluation::default();
+        for call in self.calls(memory) {
+            self.apply(&mut evaluation, &call)?;
+        }
+        Ok(evaluation)
+    }
+
+    pub fn evaluate_reader<R: Read>(&self, reader: R) -> Result<Evaluation> {
+        let mut evaluation = Evaluation::default();
+        for call in self.read_calls(reader) {
+            self.apply(&mut evaluation, &call?)?;
+        }
+        Ok(evaluation)
+    }
+}
+
+impl Default for Grammar {
+    fn default() -> Grammar {
+        Grammar::new()
+    }
+}
+
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Iterator over the calls read by `Grammar::read_calls`. Only the bytes which may be the start of an instruction
+/// are kept between the reads, so the memory is bounded by the buffer size and the longest instruction.
+pub struct Calls<'a, R: Read> {
+    grammar: &'a Grammar,
+    reader: R,
+    buffer: Vec<u8>,
+    start: usize,
+    done: bool,
+}
+
+impl<R: Read> Iterator for Calls<'_, R> {
+    type Item = io::Result<Call>;
+
+    fn next(&mut self) -> Option<io::Result<Call>> {
+        loop {
+            match self.grammar.scan::<Skip>(&self.buffer[self.start..], self.done) {
+                Scan::Found(call, span) => {
+                    self.start += span.end;
+                    return Some(Ok(call));
                 }
+                Scan::NeedMore(skipped) => self.start += skipped,
             }
-            if b == 0 {
-                continue;
+            if self.done {
+                return None;
             }
-            // Get closing parenthesis.
-            if i >= input.len() || bytes[i] != b')' {
-                continue;
+
+            self.buffer.drain(..self.start);
+            self.start = 0;
+            let kept = self.buffer.len();
+            self.buffer.resize(kept + STREAM_BUFFER_SIZE, 0);
+            let read = self.reader.read(&mut self.buffer[kept..]);
+            self.buffer.truncate(kept + *read.as_ref().unwrap_or(&0));
+            match read {
+                Ok(0) => self.done = true,
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
             }
-            i += 1;
-            // Sum result.
-            total += a * b;
-        } else {
-            i += 1;
         }
     }
-    return total;
 }
 
-pub fn parse_instructions_with_do(input: &str) -> Vec<(i32, i32)> {
-    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|(do\(\))|(don't\(\))").unwrap();
-    let mut results: Vec<(i32, i32)> = Vec::new();
-    let mut do_mul = true;
-    for captures in re.captures_iter(input) {
-        if let Some(_) = captures.get(3) {
-            do_mul = true;
-        } else if let Some(_) = captures.get(4) {
-            do_mul = false;
-        } else if do_mul {
-            if let (Some(a), Some(b)) = (captures.get(1), captures.get(2)) {
-                if let (Ok(a), Ok(b)) = (a.as_str().parse::<i32>(), b.as_str().parse::<i32>()) {
-                    results.push((a, b));
-                }
-            }
+/// The grammar of the puzzle looking for the instructions with the engine, with `with_do` the one of the second part.
+/// Each one is built once, so its regular expression is compiled only on its first use.
+pub fn puzzle_grammar(with_do: bool, engine: Engine) -> &'static Grammar {
+    static GRAMMARS: [OnceLock<Grammar>; 6] = [const { OnceLock::new() }; 6];
+    GRAMMARS[with_do as usize * 3 + engine as usize].get_or_init(|| {
+        let mut grammar = if with_do { Grammar::conditional_multiplications() } else { Grammar::multiplications() };
+        grammar.set_engine(engine);
+        grammar
+    })
+}
+
+fn conditional_multiplications() -> &'static Grammar {
+    puzzle_grammar(true, Engine::default())
+}
+
+/// Reads the instructions of the puzzle from the reader, see `Grammar::read_calls`.
+pub struct Instructions<R: Read> {
+    calls: Calls<'static, R>,
+}
+
+impl<R: Read> Instructions<R> {
+    pub fn new(reader: R) -> Instructions<R> {
+        Instructions {
+            calls: conditional_multiplications().read_calls(reader),
         }
     }
-    return results;
 }
 
-pub fn parse_instructions_with_do_and_sum(input: &str) -> i32 {
-    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|(do\(\))|(don't\(\))").unwrap();
-    let mut total = 0;
-    let mut do_mul = true;
-    for captures in re.captures_iter(input) {
-        if let Some(_) = captures.get(3) {
-            do_mul = true;
-        } else if let Some(_) = captures.get(4) {
-            do_mul = false;
-        } else if do_mul {
-            if let (Some(a), Some(b)) = (captures.get(1), captures.get(2)) {
-                if let (Ok(a), Ok(b)) = (a.as_str().parse::<i32>(), b.as_str().parse::<i32>()) {
-                    total += a * b;
+impl<R: Read> Iterator for Instructions<R> {
+    type Item = io::Result<Instruction>;
+
+    fn next(&mut self) -> Option<io::Result<Instruction>> {
+        loop {
+            match self.calls.next()? {
+                Ok(call) => {
+                    if let Some(instruction) = Instruction::from_call(self.calls.grammar, &call) {
+                        return Some(Ok(instruction));
+                    }
                 }
+                Err(e) => return Some(Err(e)),
             }
         }
     }
-    return total;
 }
 
-pub fn parse_instructions_with_do_no_regex(input: &str) -> Vec<(i32, i32)> {
-    let mut results: Vec<(i32, i32)> = Vec::new();
-    let mut do_mul = true;
+/// Returns the instructions of the puzzle in the memory, see `Grammar::tokens`.
+pub fn parse_tokens(input: &str, near_misses: bool) -> Vec<Token> {
+    conditional_multiplications().tokens(input.as_bytes(), near_misses).collect()
+}
+
+/// Returns the arguments of the enabled multiplications found by a grammar of the puzzle, see `puzzle_grammar`.
+fn enabled_multiplications(grammar: &Grammar, input: &str) -> Vec<(i32, i32)> {
+    let mut enabled = true;
+    grammar
+        .calls(input.as_bytes())
+        .filter_map(|call| match Instruction::from_call(grammar, &call)? {
+            Instruction::Mul(a, b) => enabled.then_some((a, b)),
+            Instruction::Do => {
+                enabled = true;
+                None
+            }
+            Instruction::Dont => {
+                enabled = false;
+                None
+            }
+        })
+        .collect()
+}
+
+fn sum_enabled_multiplications(grammar: &Grammar, input: &str) -> Result<i64> {
+    grammar.evaluate(input.as_bytes()).map(|evaluation| evaluation.total)
+}
+
+pub fn parse_instructions(input: &str) -> Vec<(i32, i32)> {
+    enabled_multiplications(puzzle_grammar(false, Engine::Regex), input)
+}
+
+pub fn parse_instructions_and_sum(input: &str) -> Result<i64> {
+    sum_enabled_multiplications(puzzle_grammar(false, Engine::Regex), input)
+}
+
+pub fn parse_instructions_no_regex(input: &str) -> Vec<(i32, i32)> {
+    enabled_multiplications(puzzle_grammar(false, Engine::Scalar), input)
+}
+
+pub fn parse_instructions_no_regex_and_sum(input: &str) -> Result<i64> {
+    let mut total = 0;
     let mut i = 0;
     let bytes = input.as_bytes();
-    while i < input.len() - 4 {
+    while i + 4 < input.len() {
         if bytes[i] == b'm' && bytes[i + 1] == b'u' && bytes[i + 2] == b'l' && bytes[i + 3] == b'(' {
             i += 4;
             // Get first number.
@@ -196,7 +657,7 @@ pub fn parse_instructions_with_do_no_regex(input: &str) -> Vec<(i32, i32)> {
             let max_digits = cmp::min(3, input.len() - i);
             for _ in 0..max_digits {
                 let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
+                let is_digit = c.is_ascii_digit();
                 if is_digit {
                     a = a * 10 + (c - b'0') as i32;
                     i += 1;
@@ -217,7 +678,7 @@ pub fn parse_instructions_with_do_no_regex(input: &str) -> Vec<(i32, i32)> {
             let max_digits = cmp::min(3, input.len() - i);
             for _ in 0..max_digits {
                 let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
+                let is_digit = c.is_ascii_digit();
                 if is_digit {
                     b = b * 10 + (c - b'0') as i32;
                     i += 1;
@@ -233,37 +694,33 @@ pub fn parse_instructions_with_do_no_regex(input: &str) -> Vec<(i32, i32)> {
                 continue;
             }
             i += 1;
-            // Save result.
-            if do_mul {
-                results.push((a, b));
-            }
-        } else if bytes[i] == b'd' && bytes[i + 1] == b'o' && bytes[i + 2] == b'(' && bytes[i + 3] == b')' {
-            i += 4;
-            do_mul = true;
-        } else if i < input.len() - 7
-            && bytes[i] == b'd'
-            && bytes[i + 1] == b'o'
-            && bytes[i + 2] == b'n'
-            && bytes[i + 3] == b'\''
-            && bytes[i + 4] == b't'
-            && bytes[i + 5] == b'('
-            && bytes[i + 6] == b')'
-        {
-            i += 7;
-            do_mul = false;
+            // Sum result.
+            total = add_product(total, a, b)?;
         } else {
             i += 1;
         }
     }
-    return results;
+    Ok(total)
+}
+
+pub fn parse_instructions_with_do(input: &str) -> Vec<(i32, i32)> {
+    enabled_multiplications(puzzle_grammar(true, Engine::Regex), input)
+}
+
+pub fn parse_instructions_with_do_and_sum(input: &str) -> Result<i64> {
+    sum_enabled_multiplications(puzzle_grammar(true, Engine::Regex), input)
 }
 
-pub fn parse_instructions_with_do_no_regex_and_sum(input: &str) -> i32 {
+pub fn parse_instructions_with_do_no_regex(input: &str) -> Vec<(i32, i32)> {
+    enabled_multiplications(puzzle_grammar(true, Engine::Scalar), input)
+}
+
+pub fn parse_instructions_with_do_no_regex_and_sum(input: &str) -> Result<i64> {
     let mut total = 0;
     let mut do_mul = true;
     let mut i = 0;
     let bytes = input.as_bytes();
-    while i < input.len() - 4 {
+    while i + 4 < input.len() {
         if bytes[i] == b'm' && bytes[i + 1] == b'u' && bytes[i + 2] == b'l' && bytes[i + 3] == b'(' {
             i += 4;
             // Get first number.
@@ -271,7 +728,7 @@ pub fn parse_instructions_with_do_no_regex_and_sum(input: &str) -> i32 {
             let max_digits = cmp::min(3, input.len() - i);
             for _ in 0..max_digits {
                 let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
+                let is_digit = c.is_ascii_digit();
                 if is_digit {
                     a = a * 10 + (c - b'0') as i32;
                     i += 1;
@@ -292,7 +749,7 @@ pub fn parse_instructions_with_do_no_regex_and_sum(input: &str) -> i32 {
             let max_digits = cmp::min(3, input.len() - i);
             for _ in 0..max_digits {
                 let c = bytes[i];
-                let is_digit = c >= b'0' && c <= b'9';
+                let is_digit = c.is_ascii_digit();
                 if is_digit {
                     b = b * 10 + (c - b'0') as i32;
                     i += 1;
@@ -310,12 +767,12 @@ pub fn parse_instructions_with_do_no_regex_and_sum(input: &str) -> i32 {
             i += 1;
             // Sum result.
             if do_mul {
-                total += a * b;
+                total = add_product(total, a, b)?;
             }
         } else if bytes[i] == b'd' && bytes[i + 1] == b'o' && bytes[i + 2] == b'(' && bytes[i + 3] == b')' {
             i += 4;
             do_mul = true;
-        } else if i < input.len() - 7
+        } else if i + 7 < input.len()
             && bytes[i] == b'd'
             && bytes[i + 1] == b'o'
             && bytes[i + 2] == b'n'
@@ -330,15 +787,102 @@ pub fn parse_instructions_with_do_no_regex_and_sum(input: &str) -> i32 {
             i += 1;
         }
     }
-    return total;
+    Ok(total)
+}
+
+/// Parses a number of 1 to 3 digits at the start of the bytes, returning it and its length.
+fn parse_number(bytes: &[u8]) -> Option<(i32, usize)> {
+    let length = bytes.iter().take(3).take_while(|c| c.is_ascii_digit()).count();
+    if length == 0 {
+        return None;
+    }
+    Some((bytes[..length].iter().fold(0, |n, c| n * 10 + (c - b'0') as i32), length))
+}
+
+/// Parses a `mul(a,b)` instruction at the start of the bytes, returning the product and its length.
+fn parse_mul(bytes: &[u8]) -> Option<(i32, usize)> {
+    let (a, length_a) = parse_number(bytes.strip_prefix(b"mul(")?)?;
+    let i = 4 + length_a;
+    let (b, length_b) = parse_number(bytes[i..].strip_prefix(b",")?)?;
+    let i = i + 1 + length_b;
+    if bytes.get(i) != Some(&b')') {
+        return None;
+    }
+    Some((a * b, i + 1))
+}
+
+/// Same as `parse_instructions_no_regex_and_sum`, but jumping between the 'm' bytes with `memchr`,
+/// which uses the vector instructions of the CPU when available and a scalar search otherwise.
+pub fn parse_instructions_memchr_and_sum(input: &str) -> Result<i64> {
+    let bytes = input.as_bytes();
+    // A matched instruction contains no other 'm', so no position needs to be skipped.
+    memchr::memchr_iter(b'm', bytes)
+        .filter_map(|i| parse_mul(&bytes[i..]))
+        .try_fold(0, |total, (product, _)| add_product(total, product, 1))
+}
+
+/// Same as `parse_instructions_with_do_no_regex_and_sum`, but jumping between the 'm' and 'd' bytes with `memchr`.
+pub fn parse_instructions_with_do_memchr_and_sum(input: &str) -> Result<i64> {
+    let bytes = input.as_bytes();
+    let mut total = 0;
+    let mut do_mul = true;
+    for i in memchr::memchr2_iter(b'm', b'd', bytes) {
+        let rest = &bytes[i..];
+        if rest[0] == b'm' {
+            match parse_mul(rest) {
+                Some((product, _)) if do_mul => total = add_product(total, product, 1)?,
+                _ => {}
+            }
+        } else if rest.starts_with(b"do()") {
+            do_mul = true;
+        } else if rest.starts_with(b"don't()") {
+            do_mul = false;
+        }
+    }
+    Ok(total)
+}
+
+/// Sums the multiplications read from the reader, with `with_do` only the enabled ones.
+pub fn sum_instructions_stream<R: Read>(reader: R, with_do: bool) -> Result<i64> {
+    puzzle_grammar(with_do, Engine::default()).evaluate_reader(reader).map(|evaluation| evaluation.total)
 }
 
-pub fn sum_instructions(instructions: &[(i32, i32)]) -> i32 {
+pub fn sum_instructions(instructions: &[(i32, i32)]) -> Result<i64> {
     let mut total = 0;
-    for (a, b) in instructions {
-        total += a * b;
+    for &(a, b) in instructions {
+        total = add_product(total, a, b)?;
+    }
+    Ok(total)
+}
+
+#[derive(Default)]
+pub struct Day03 {
+    memory: String,
+    /// The sum of all the multiplications, which is the answer of the first part.
+    total: i64,
+}
+
+impl Solution for Day03 {
+    fn day(&self) -> u32 {
+        3
+    }
+
+    /// Sums all the multiplications, so that an overflow is reported as an error of the input.
+    /// The products are not negative, so the sum of just the enabled ones cannot overflow either.
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.total = parse_instructions_memchr_and_sum(input)?;
+        self.memory = input.to_string();
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The sum of multiplication of instructions is {}", self.total)
+    }
+
+    fn part_two(&self) -> Answer {
+        let total = parse_instructions_with_do_memchr_and_sum(&self.memory).expect("Error: The sum was checked when parsing");
+        Answer::new("The sum of multiplication of instructions of just enabled multiplications is {}", total)
     }
-    return total;
 }
 
 #[cfg(test)]
@@ -361,7 +905,7 @@ mod tests {
 
     #[test]
     fn test_parse_instructions_and_sum_example() {
-        assert_eq!(parse_instructions_and_sum(EXAMPLE_INPUT), 161);
+        assert_eq!(parse_instructions_and_sum(EXAMPLE_INPUT).unwrap(), 161);
     }
 
     #[test]
@@ -371,7 +915,7 @@ mod tests {
 
     #[test]
     fn test_parse_instructions_no_regex_and_sum_example() {
-        assert_eq!(parse_instructions_no_regex_and_sum(EXAMPLE_INPUT), 161);
+        assert_eq!(parse_instructions_no_regex_and_sum(EXAMPLE_INPUT).unwrap(), 161);
     }
 
     #[test]
@@ -387,7 +931,7 @@ mod tests {
 
     #[test]
     fn test_parse_instructions_with_do_and_sum_example() {
-        assert_eq!(parse_instructions_with_do_and_sum(EXAMPLE_DO_INPUT), 48);
+        assert_eq!(parse_instructions_with_do_and_sum(EXAMPLE_DO_INPUT).unwrap(), 48);
     }
 
     #[test]
@@ -397,18 +941,367 @@ mod tests {
 
     #[test]
     fn test_parse_instructions_with_do_no_regex_and_sum_example() {
-        assert_eq!(parse_instructions_with_do_no_regex_and_sum(EXAMPLE_DO_INPUT), 48);
+        assert_eq!(parse_instructions_with_do_no_regex_and_sum(EXAMPLE_DO_INPUT).unwrap(), 48);
+    }
+
+    #[test]
+    fn test_parse_instructions_short_input() {
+        for input in ["", "m", "mul", "mul(", "do()", "mul(1,"] {
+            assert_eq!(parse_instructions_no_regex(input), []);
+            assert_eq!(parse_instructions_no_regex_and_sum(input).unwrap(), 0);
+            assert_eq!(parse_instructions_with_do_no_regex(input), []);
+            assert_eq!(parse_instructions_with_do_no_regex_and_sum(input).unwrap(), 0);
+        }
+    }
+
+    /// Reads one byte at a time, so every instruction is split across reads.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((&byte, rest)) if !buffer.is_empty() => {
+                    buffer[0] = byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    fn scan(input: &str) -> Vec<Instruction> {
+        let instructions: Vec<Instruction> = Instructions::new(input.as_bytes()).map(|i| i.unwrap()).collect();
+        let split: Vec<Instruction> = Instructions::new(ByteReader(input.as_bytes())).map(|i| i.unwrap()).collect();
+        assert_eq!(instructions, split);
+        instructions
+    }
+
+    #[test]
+    fn test_instructions() {
+        use Instruction::*;
+        assert_eq!(scan(EXAMPLE_INPUT), [Mul(2, 4), Mul(5, 5), Mul(11, 8), Mul(8, 5)]);
+        assert_eq!(scan(EXAMPLE_DO_INPUT), [Mul(2, 4), Dont, Mul(5, 5), Mul(11, 8), Do, Mul(8, 5)]);
+        assert_eq!(scan(""), []);
+        assert_eq!(scan("mul(0,5)mul(1234,5)mul(123,4567)mul(,1)mul(1,)"), [Mul(0, 5)]);
+        assert_eq!(scan("mumul(1,2)mul(mul(3,4)dodo()dondon't()do(don't()"), [Mul(1, 2), Mul(3, 4), Do, Dont, Dont]);
+        assert_eq!(scan("mul(2,3)do"), [Mul(2, 3)]);
+    }
+
+    #[test]
+    fn test_instruction_from_call() {
+        // The instructions are recognized by their names, whatever the order of the operations.
+        let mut grammar = Grammar::new();
+        grammar.register("don't", 0, 0, |_, _| Ok(())).unwrap();
+        grammar.register("add", 2, 3, |_, _| Ok(())).unwrap();
+        grammar.register("mul", 2, 3, |_, _| Ok(())).unwrap();
+        grammar.register("do", 0, 0, |_, _| Ok(())).unwrap();
+        let instructions: Vec<Option<Instruction>> = grammar.calls(b"do()add(1,2)mul(3,4)don't()").map(|call| Instruction::from_call(&grammar, &call)).collect();
+        assert_eq!(instructions, [Some(Instruction::Do), None, Some(Instruction::Mul(3, 4)), Some(Instruction::Dont)]);
+    }
+
+    #[test]
+    fn test_sum_instructions_stream() {
+        assert_eq!(sum_instructions_stream(EXAMPLE_INPUT.as_bytes(), false).unwrap(), 161);
+        assert_eq!(sum_instructions_stream(ByteReader(EXAMPLE_DO_INPUT.as_bytes()), true).unwrap(), 48);
+        assert_eq!(sum_instructions_stream(ByteReader(EXAMPLE_DO_INPUT.as_bytes()), false).unwrap(), 161);
+        // Larger than the buffer, with instructions across the buffer boundaries.
+        let memory = "xmul(2,4)don't()mul(5,5)do()".repeat(10_000);
+        assert_eq!(sum_instructions_stream(memory.as_bytes(), true).unwrap(), 80_000);
+    }
+
+    #[test]
+    fn test_grammar() {
+        let memory = EXAMPLE_DO_INPUT.as_bytes();
+        assert_eq!(Grammar::multiplications().evaluate(memory).unwrap().total, 161);
+        assert_eq!(Grammar::conditional_multiplications().evaluate(memory).unwrap().total, 48);
+        assert_eq!(Grammar::conditional_multiplications().evaluate_reader(ByteReader(memory)).unwrap().total, 48);
+        assert!(!Grammar::conditional_multiplications().evaluate(b"do()don't()").unwrap().enabled);
+        assert_eq!(Grammar::new().evaluate(memory).unwrap(), Evaluation::default());
+    }
+
+    #[test]
+    fn test_grammar_default() {
+        let mut grammar = Grammar::default();
+        assert_eq!(grammar.evaluate(b"mul(2,3)").unwrap(), Evaluation::default());
+        grammar
+            .register("mul", 2, 3, |evaluation, args| {
+                evaluation.total += args[0] as i64 * args[1] as i64;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(grammar.evaluate(b"mul(2,3)").unwrap().total, 6);
+    }
+
+    #[test]
+    fn test_grammar_register() {
+        let mut grammar = Grammar::conditional_multiplications();
+        grammar
+            .register("add", 2, 2, |evaluation, args| {
+                evaluation.total += (args[0] + args[1]) as i64;
+                Ok(())
+            })
+            .unwrap();
+        grammar
+            .register("sum", 3, 1, |evaluation, args| {
+                evaluation.total += args.iter().sum::<i32>() as i64;
+                Ok(())
+            })
+            .unwrap();
+        grammar
+            .register("reset", 0, 0, |evaluation, _| {
+                evaluation.total = 0;
+                Ok(())
+            })
+            .unwrap();
+        let memory = b"add(1,2)xadd(123,4)sum(1,2,3)sum(1,2)mul(2,3)don't()add(10,10)reset(0)reset()";
+        let calls: Vec<Call> = grammar.calls(memory).collect();
+        let names: Vec<&str> = calls.iter().map(|call| grammar.operation(call.operation).name.as_str()).collect();
+        assert_eq!(names, ["add", "sum", "mul", "don't", "add", "reset"]);
+        assert_eq!(calls[1].args(), [1, 2, 3]);
+
+        let memory = b"add(1,2)sum(1,2,3)mul(2,3)don't()add(10,10)";
+        // Only the multiplications check whether they are enabled.
+        assert_eq!(grammar.evaluate(memory).unwrap().total, 3 + 6 + 6 + 20);
+        let streamed: Vec<Call> = grammar.read_calls(ByteReader(memory)).map(|call| call.unwrap()).collect();
+        assert_eq!(streamed, grammar.calls(memory).collect::<Vec<Call>>());
+    }
+
+    #[test]
+    fn test_grammar_regex() {
+        let mut grammar = Grammar::conditional_multiplications();
+        grammar
+            .register("add", 2, 2, |evaluation, args| {
+                evaluation.total += (args[0] + args[1]) as i64;
+                Ok(())
+            })
+            .unwrap();
+        let memory = b"add(1,2)xadd(123,4)mul(2,3)mul(1234,5)don't()add(10,10)do(x)do()mul(4,5)";
+        let scanned: Vec<Call> = grammar.calls(memory).collect();
+        grammar.set_engine(Engine::Regex);
+        assert_eq!(grammar.calls(memory).collect::<Vec<Call>>(), scanned);
+        assert_eq!(grammar.evaluate(memory).unwrap().total, 3 + 6 + 20 + 20);
+        assert_eq!(Grammar::new().set_engine(Engine::Regex).calls(memory).count(), 0);
+    }
+
+    #[test]
+    fn test_grammar_register_errors() {
+        let mut grammar = Grammar::new();
+        let error = grammar.register("", 0, 0, |_, _| Ok(())).err().unwrap();
+        assert_eq!(error.to_string(), "invalid operation name ''");
+        assert!(grammar.register("mül", 1, 3, |_, _| Ok(())).is_err());
+        let error = grammar.register("mul", 2, 10, |_, _| Ok(())).err().unwrap();
+        assert_eq!(error.to_string(), "invalid number of digits 10 for 'mul', expected 1 to 9");
+        assert!(grammar.register("mul", 2, 0, |_, _| Ok(())).is_err());
+        let error = grammar.register("sum", 5, 3, |_, _| Ok(())).err().unwrap();
+        assert_eq!(error.to_string(), "invalid arity 5 for 'sum', expected at most 4");
+        assert_eq!(grammar.evaluate(b"mul(2,3)").unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_grammar_order() {
+        // The operation registered first wins, even when it needs more bytes to decide.
+        let mut grammar = Grammar::new();
+        grammar.register("ab", 1, 3, |_, _| Ok(())).unwrap();
+        grammar.register("a", 0, 0, |_, _| Ok(())).unwrap();
+        let memory = b"ab(1)a()ab()a(1)";
+        assert_eq!(grammar.calls(memory).map(|call| call.operation).collect::<Vec<usize>>(), [0, 1]);
+        let streamed: Vec<usize> = grammar.read_calls(ByteReader(memory)).map(|call| call.unwrap().operation).collect();
+        assert_eq!(streamed, [0, 1]);
+    }
+
+    #[test]
+    fn test_parse_tokens() {
+        let tokens = parse_tokens(EXAMPLE_DO_INPUT, false);
+        let spans: Vec<(Range<usize>, bool)> = tokens
+            .iter()
+            .map(|token| (token.span().clone(), matches!(token, Token::Call { enabled: true, .. })))
+            .collect();
+        assert_eq!(spans, [(1..9, true), (20..27, true), (28..36, false), (48..57, false), (59..63, false), (64..72, true)]);
+        assert_eq!(&EXAMPLE_DO_INPUT[28..36], "mul(5,5)");
+        let total: i64 = tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Call { call, enabled: true, .. } if call.operation == 0 => Some(call.args()[0] as i64 * call.args()[1] as i64),
+                _ => None,
+            })
+            .sum();
+        assert_eq!(total, 48);
+    }
+
+    #[test]
+    fn test_near_misses() {
+        let input = "mul(32,64]mul[3,7]mul(1234,5)mul(,2)do(x)mul(2,3)mul(4*";
+        let tokens = parse_tokens(input, true);
+        let near_misses: Vec<(&str, Rejection)> = tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::NearMiss { span, rejection, .. } => Some((&input[span.clone()], *rejection)),
+                Token::Call { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            near_misses,
+            [
+                ("mul(32,64]", Rejection::Expected(b')')),
+                ("mul[", Rejection::Expected(b'(')),
+                ("mul(1234", Rejection::TooManyDigits),
+                ("mul(,", Rejection::MissingDigits),
+                ("do(x", Rejection::Expected(b')')),
+                ("mul(4*", Rejection::Expected(b',')),
+            ]
+        );
+        assert_eq!(tokens.iter().filter(|token| matches!(token, Token::Call { .. })).count(), 1);
+        assert_eq!(Rejection::Expected(b')').to_string(), "expected ')'");
+        assert_eq!(parse_tokens(input, false).len(), 1);
+    }
+
+    #[test]
+    fn test_near_misses_overlapping_names() {
+        fn near_miss(input: &str) -> Option<(usize, &str, Rejection)> {
+            match parse_tokens(input, true).first() {
+                Some(Token::NearMiss { operation, span, rejection }) => Some((*operation, &input[span.clone()], *rejection)),
+                _ => None,
+            }
+        }
+        // The rejection of `don't` goes further than the one of `do`.
+        assert_eq!(near_miss("don't(x)"), Some((2, "don't(x", Rejection::Expected(b')'))));
+        assert_eq!(near_miss("do(x)"), Some((1, "do(x", Rejection::Expected(b')'))));
+
+        let mut grammar = Grammar::new();
+        grammar.register("m", 1, 3, |_, _| Ok(())).unwrap();
+        grammar.register("mul", 2, 3, |_, _| Ok(())).unwrap();
+        let tokens: Vec<Token> = grammar.tokens(b"mul(1,2]", true).collect();
+        assert_eq!(
+            tokens.first(),
+            Some(&Token::NearMiss {
+                operation: 1,
+                span: 0..8,
+                rejection: Rejection::Expected(b')'),
+            })
+        );
+        let tokens: Vec<Token> = grammar.tokens(b"m(12]", true).collect();
+        assert!(matches!(tokens.first(), Some(Token::NearMiss { operation: 0, .. })));
+    }
+
+    #[test]
+    fn test_parse_instructions_memchr() {
+        assert_eq!(parse_instructions_memchr_and_sum(EXAMPLE_INPUT).unwrap(), 161);
+        assert_eq!(parse_instructions_with_do_memchr_and_sum(EXAMPLE_DO_INPUT).unwrap(), 48);
+        let inputs = [
+            "",
+            "m",
+            "mul(",
+            "mul(2,3)",
+            "mul(2,3",
+            "mul(1234,5)mul(12,345)",
+            "mul(0,7)mul(00,7)mul(1,002)",
+            "mulmul(2,mul(3,4))",
+            "don't()mul(2,3)do()mul(4,5)",
+            "don't()mul(2,3)do(",
+            "mul(2,3)don't(",
+            "ddo()don'tdon't()mmul(1,1)",
+        ];
+        for input in inputs {
+            assert_eq!(
+                parse_instructions_memchr_and_sum(input).unwrap(),
+                parse_instructions_no_regex_and_sum(input).unwrap(),
+                "{}",
+                input
+            );
+            assert_eq!(
+                parse_instructions_with_do_memchr_and_sum(input).unwrap(),
+                parse_instructions_with_do_no_regex_and_sum(input).unwrap(),
+                "{}",
+                input
+            );
+            for with_do in [false, true] {
+                assert_eq!(
+                    puzzle_grammar(with_do, Engine::Memchr).evaluate(input.as_bytes()).unwrap().total,
+                    puzzle_grammar(with_do, Engine::Scalar).evaluate(input.as_bytes()).unwrap().total,
+                    "{}",
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_grammar_engines() {
+        // With more than three first bytes the memchr engine falls back to the scalar search.
+        let mut grammar = Grammar::conditional_multiplications();
+        for name in ["add", "sub", "xor"] {
+            grammar.register(name, 2, 3, |_, _| Ok(())).unwrap();
+        }
+        let memory = b"xadd(1,2)sub(3,4]mul(5,6)xor(7,8)ddon't()do()sus";
+        let calls = |grammar: &Grammar| grammar.calls(memory).map(|call| call.operation).collect::<Vec<usize>>();
+        let expected = calls(&grammar);
+        assert_eq!(expected, [3, 0, 5, 2, 1]);
+        for engine in [Engine::Scalar, Engine::Regex] {
+            grammar.set_engine(engine);
+            assert_eq!(calls(&grammar), expected);
+        }
     }
 
     #[test]
     fn test_sum_instructions() {
         let instructions = [(1, 2), (3, 4), (5, 6)];
-        assert_eq!(sum_instructions(&instructions), 44);
+        assert_eq!(sum_instructions(&instructions).unwrap(), 44);
     }
 
     #[test]
     fn test_sum_instructions_example() {
         let instructions = [(2, 4), (5, 5), (11, 8), (8, 5)];
-        assert_eq!(sum_instructions(&instructions), 161);
+        assert_eq!(sum_instructions(&instructions).unwrap(), 161);
+    }
+
+    #[test]
+    fn test_sum_beyond_i32() {
+        // The sums in i32 overflowed after 2152 multiplications of 999 by 999.
+        let input = "mul(999,999)don't()mul(1,1)do()".repeat(3000);
+        let expected = 3000 * 999 * 999;
+        assert!(expected > i32::MAX as i64);
+        assert_eq!(parse_instructions_and_sum(&input).unwrap(), expected + 3000);
+        assert_eq!(parse_instructions_no_regex_and_sum(&input).unwrap(), expected + 3000);
+        assert_eq!(parse_instructions_memchr_and_sum(&input).unwrap(), expected + 3000);
+        assert_eq!(parse_instructions_with_do_and_sum(&input).unwrap(), expected);
+        assert_eq!(parse_instructions_with_do_no_regex_and_sum(&input).unwrap(), expected);
+        assert_eq!(parse_instructions_with_do_memchr_and_sum(&input).unwrap(), expected);
+        assert_eq!(sum_instructions_stream(input.as_bytes(), true).unwrap(), expected);
+        assert_eq!(sum_instructions(&parse_instructions_with_do(&input)).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_grammar_overflow() {
+        // Nine-digit arguments overflow the i64 total after ten multiplications.
+        let mut grammar = Grammar::new();
+        grammar
+            .register("mul", 2, 9, |evaluation, args| {
+                evaluation.total = add_product(evaluation.total, args[0], args[1])?;
+                Ok(())
+            })
+            .unwrap();
+        let memory = "mul(999999999,999999999)".repeat(10);
+        assert!(grammar.evaluate(&memory.as_bytes()[..24 * 9]).is_ok());
+        let error = grammar.evaluate(memory.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "the sum of the multiplications overflows");
+        assert!(grammar.evaluate_reader(memory.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        let instructions = [(i32::MAX, i32::MAX), (i32::MIN, i32::MIN), (i32::MAX, i32::MAX)];
+        let error = sum_instructions(&instructions).unwrap_err();
+        assert_eq!(error.to_string(), "the sum of the multiplications overflows");
+        assert!(sum_instructions(&instructions[..1]).is_ok());
+    }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day03::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "161");
+        solution.parse(EXAMPLE_DO_INPUT).unwrap();
+        assert_eq!(solution.part_two().value, "48");
     }
 }
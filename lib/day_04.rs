@@ -1,4 +1,9 @@
-﻿const XMAS: u32 = (('X' as u32) << 0) | (('M' as u32) << 8) | (('A' as u32) << 16) | (('S' as u32) << 24);
+﻿use crate::error::Result;
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::solution::{Answer, Solution};
+
+const XMAS: u32 = (('X' as u32) << 0) | (('M' as u32) << 8) | (('A' as u32) << 16) | (('S' as u32) << 24);
 const XMAS_REVERSE: u32 = (('S' as u32) << 0) | (('A' as u32) << 8) | (('M' as u32) << 16) | (('X' as u32) << 24);
 
 const MAS: u32 = (('M' as u32) << 0) | (('A' as u32) << 8) | (('S' as u32) << 16);
@@ -6,30 +11,19 @@ const MAS_REVERSE: u32 = (('S' as u32) << 0) | (('A' as u32) << 8) | (('M' as u3
 
 const MAS_MASK: u32 = (('M' as u32 + 'S' as u32) << 0) | (('M' as u32 + 'S' as u32) << 8);
 
+#[derive(Default)]
 pub struct WordSearch {
-    words: Vec<char>,
-    width: i32,
-    height: i32,
+    words: Grid<char>,
 }
 
 impl WordSearch {
-    pub fn new(words: String) -> WordSearch {
-        let width = words.find('\n').unwrap() as i32;
-        let height = (words.len() as i32) / (width + 1);
-        WordSearch {
-            words: words.chars().collect(),
-            width,
-            height,
-        }
+    pub fn parse(words: &str) -> Result<WordSearch> {
+        Ok(WordSearch { words: Grid::parse(words)? })
     }
 
     #[inline]
     fn get(&self, x: i32, y: i32) -> char {
-        if x < 0 || x >= self.width || y < 0 || y >= self.height {
-            return 0 as char;
-        }
-
-        return self.words[(y * (self.width + 1) + x) as usize];
+        return self.words.get_or(Point::new(x, y), 0 as char);
     }
 
     #[inline]
@@ -100,8 +94,8 @@ impl WordSearch {
 
     pub fn count_xmas(&self) -> usize {
         let mut count = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
+        for y in 0..self.words.height() as i32 {
+            for x in 0..self.words.width() as i32 {
                 let horizontal = self.get_horizontal(x, y);
                 if horizontal == XMAS || horizontal == XMAS_REVERSE {
                     count += 1;
@@ -125,8 +119,8 @@ impl WordSearch {
 
     pub fn count_xmas_check_xs(&self) -> usize {
         let mut count = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
+        for y in 0..self.words.height() as i32 {
+            for x in 0..self.words.width() as i32 {
                 let c = self.get(x, y);
                 if c != 'X' && c != 'S' {
                     continue;
@@ -154,8 +148,8 @@ impl WordSearch {
 
     pub fn count_mas_diagonal(&self) -> usize {
         let mut count = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
+        for y in 0..self.words.height() as i32 {
+            for x in 0..self.words.width() as i32 {
                 let diagonal_right = self.get_x_diagonal_right(x, y);
                 let diagonal_left = self.get_x_diagonal_left(x, y);
                 if (diagonal_right == MAS || diagonal_right == MAS_REVERSE) && (diagonal_left == MAS || diagonal_left == MAS_REVERSE) {
@@ -168,8 +162,8 @@ impl WordSearch {
 
     pub fn count_mas_diagonal_check_a(&self) -> usize {
         let mut count = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
+        for y in 0..self.words.height() as i32 {
+            for x in 0..self.words.width() as i32 {
                 let c = self.get(x, y);
                 if c != 'A' {
                     continue;
@@ -186,8 +180,8 @@ impl WordSearch {
 
     pub fn count_mas_diagonal_check_mask(&self) -> usize {
         let mut count = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
+        for y in 0..self.words.height() as i32 {
+            for x in 0..self.words.width() as i32 {
                 let c = self.get(x, y);
                 if c != 'A' {
                     continue;
@@ -203,8 +197,8 @@ impl WordSearch {
 
     pub fn count_mas_diagonal_check_if(&self) -> usize {
         let mut count = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
+        for y in 0..self.words.height() as i32 {
+            for x in 0..self.words.width() as i32 {
                 let c = self.get(x, y);
                 if c != 'A' {
                     continue;
@@ -229,6 +223,30 @@ impl WordSearch {
     }
 }
 
+#[derive(Default)]
+pub struct Day04 {
+    word_search: WordSearch,
+}
+
+impl Solution for Day04 {
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.word_search = WordSearch::parse(input)?;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The word XMAS appears {} times", self.word_search.count_xmas_check_xs())
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new("The word MAS appears diagonally {} times", self.word_search.count_mas_diagonal_check_mask())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,15 +266,15 @@ MXMXAXMASX
     #[test]
     fn test_get_size() {
         let words = "XMAS\nXMAS\nXMAS\n".to_string();
-        let ws = WordSearch::new(words);
-        assert_eq!(ws.width, 4);
-        assert_eq!(ws.height, 3);
+        let ws = WordSearch::parse(&words).unwrap();
+        assert_eq!(ws.words.width(), 4);
+        assert_eq!(ws.words.height(), 3);
     }
 
     #[test]
     fn test_get() {
         let words = "XMAS\nXMAS\nXMAS\nXMAS\nXMAS\n".to_string();
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.get(0, 0), 'X');
         assert_eq!(ws.get(1, 1), 'M');
         assert_eq!(ws.get(2, 0), 'A');
@@ -266,7 +284,7 @@ MXMXAXMASX
     #[test]
     fn test_get_word() {
         let words = "XMASAMXMAS\nMMASAMXMAS\nAMASAMXMAS\nSMASAMXMAS\n".to_string();
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.get_horizontal(0, 0), XMAS);
         assert_eq!(ws.get_horizontal(3, 2), XMAS_REVERSE);
         assert_eq!(ws.get_vertical(0, 0), XMAS);
@@ -278,7 +296,7 @@ MXMXAXMASX
     #[test]
     fn test_get_world_outside_bounds() {
         let words = "XMAS\nXMAS\nXMAS\nXMAS\n".to_string();
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.get_horizontal(12, 5), 0);
         assert_eq!(ws.get_vertical(2, 8), 0);
         assert_eq!(ws.get_diagonal_right(12, 0), 0);
@@ -288,7 +306,7 @@ MXMXAXMASX
     #[test]
     fn test_count_xmas() {
         let words = "XMAS\nXMXX\nXXAX\nXXXS\nSAMX\n".to_string();
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_xmas(), 3);
     }
 
@@ -296,7 +314,7 @@ MXMXAXMASX
     fn test_count_xmas_example() {
         let words = EXAMPLE_INPUT.to_string();
 
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_xmas(), 18);
     }
 
@@ -304,14 +322,14 @@ MXMXAXMASX
     fn test_count_xmas_check_xs_example() {
         let words = EXAMPLE_INPUT.to_string();
 
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_xmas_check_xs(), 18);
     }
 
     #[test]
     fn test_count_max_diagonal() {
         let words = "MAS\nMAS\nMXS\n".to_string();
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_mas_diagonal(), 1);
     }
 
@@ -319,7 +337,7 @@ MXMXAXMASX
     fn test_count_mas_diagonal_example() {
         let words = EXAMPLE_INPUT.to_string();
 
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_mas_diagonal(), 9);
     }
 
@@ -327,7 +345,7 @@ MXMXAXMASX
     fn test_count_mas_diagonal_check_a_example() {
         let words = EXAMPLE_INPUT.to_string();
 
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_mas_diagonal_check_a(), 9);
     }
 
@@ -335,7 +353,7 @@ MXMXAXMASX
     fn test_count_mas_diagonal_check_mask_example() {
         let words = EXAMPLE_INPUT.to_string();
 
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_mas_diagonal_check_mask(), 9);
     }
 
@@ -343,7 +361,7 @@ MXMXAXMASX
     fn test_count_mas_diagonal_check_if_example() {
         let words = EXAMPLE_INPUT.to_string();
 
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_mas_diagonal_check_if(), 9);
     }
 
@@ -361,7 +379,15 @@ M.M.M.M.M.
 ..........
 "#
         .to_string();
-        let ws = WordSearch::new(words);
+        let ws = WordSearch::parse(&words).unwrap();
         assert_eq!(ws.count_mas_diagonal(), 9);
     }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day04::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "18");
+        assert_eq!(solution.part_two().value, "9");
+    }
 }
@@ -1,32 +1,38 @@
-﻿use std::io::BufRead;
+﻿use crate::error::{parse_number, Error, Result};
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
-pub fn parse_pages<R: BufRead>(reader: R) -> (Vec<(i32, i32)>, Vec<Vec<i32>>) {
+/// Page ordering rules and the pages to produce.
+pub type Pages = (Vec<(i32, i32)>, Vec<Vec<i32>>);
+
+pub fn parse_pages<R: BufRead>(reader: R) -> Result<Pages> {
     let mut page_ordering_rules: Vec<(i32, i32)> = Vec::new();
     let mut pages_to_produce: Vec<Vec<i32>> = Vec::new();
 
     let mut parse_rules = true;
 
-    for line in reader.lines() {
-        if let Ok(line_content) = line {
-            if line_content.is_empty() {
-                parse_rules = false;
-            } else if parse_rules {
-                let parts: Vec<&str> = line_content.split('|').collect();
-                if parts.len() == 2 {
-                    if let (Ok(first), Ok(second)) = (parts[0].parse::<i32>(), parts[1].parse::<i32>()) {
-                        page_ordering_rules.push((first, second));
-                    }
-                }
-            } else {
-                let parse_pages: Vec<i32> = line_content.split(',').filter_map(|s| s.parse::<i32>().ok()).collect();
-                pages_to_produce.push(parse_pages);
-            }
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line_content = line?;
+        let line_number = line_idx + 1;
+        if line_content.is_empty() {
+            parse_rules = false;
+        } else if parse_rules {
+            let (first, second) = match line_content.split_once('|') {
+                Some(parts) => parts,
+                None => return Err(Error::parse(line_number, line_content.chars().count() + 1, "expected '|'")),
+            };
+            let first = parse_number(&line_content, line_number, first)?;
+            let second = parse_number(&line_content, line_number, second)?;
+            page_ordering_rules.push((first, second));
+        } else {
+            let parse_pages = line_content.split(',').map(|s| parse_number(&line_content, line_number, s)).collect::<Result<Vec<i32>>>()?;
+            pages_to_produce.push(parse_pages);
         }
     }
 
     page_ordering_rules.sort();
 
-    (page_ordering_rules, pages_to_produce)
+    Ok((page_ordering_rules, pages_to_produce))
 }
 
 fn check_page_rules(page_ordering_rules: &Vec<(i32, i32)>, page: &Vec<i32>) -> bool {
@@ -87,6 +93,41 @@ pub fn correct_pages_to_produce(page_ordering_rules: &Vec<(i32, i32)>, pages_to_
     }
 }
 
+#[derive(Default)]
+pub struct Day05 {
+    page_ordering_rules: Vec<(i32, i32)>,
+    pages_to_produce: Vec<Vec<i32>>,
+}
+
+impl Solution for Day05 {
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        (self.page_ordering_rules, self.pages_to_produce) = parse_pages(input.as_bytes())?;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new(
+            "The sum of valid middle pages is {}",
+            sum_of_valid_middle_pages(&self.page_ordering_rules, &self.pages_to_produce),
+        )
+    }
+
+    fn part_two(&self) -> Answer {
+        let sum_of_valid_middle_pages = sum_of_valid_middle_pages(&self.page_ordering_rules, &self.pages_to_produce);
+
+        let mut pages_to_produce = self.pages_to_produce.clone();
+        correct_pages_to_produce(&self.page_ordering_rules, &mut pages_to_produce);
+
+        // After correction, all pages are valid
+        let sum_of_all_middle_pages = sum_of_all_middle_pages(&pages_to_produce);
+        Answer::new("The sum of corrected middle pages is {}", sum_of_all_middle_pages - sum_of_valid_middle_pages)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,16 +166,25 @@ mod tests {
     fn test_parse_pages() {
         let data = "56|78\n12|34\n\n12,34,56\n78,56,12\n";
         let cursor = Cursor::new(data);
-        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor);
+        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor).unwrap();
 
         assert_eq!(page_ordering_rules, vec![(12, 34), (56, 78)]);
         assert_eq!(pages_to_produce, vec![vec![12, 34, 56], vec![78, 56, 12]]);
     }
 
+    #[test]
+    fn test_parse_pages_errors() {
+        let error = parse_pages(Cursor::new("56|78\n12-34\n")).unwrap_err();
+        assert_eq!(error.to_string(), "2:6: expected '|'");
+
+        let error = parse_pages(Cursor::new("56|78\n\n12,34,\n")).unwrap_err();
+        assert_eq!(error.to_string(), "3:7: invalid number ''");
+    }
+
     #[test]
     fn test_check_page_rules() {
         let cursor = Cursor::new(EXAMPLE_INPUT);
-        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor);
+        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor).unwrap();
 
         assert_eq!(check_page_rules(&page_ordering_rules, &pages_to_produce[0]), true);
         assert_eq!(check_page_rules(&page_ordering_rules, &pages_to_produce[1]), true);
@@ -154,7 +204,7 @@ mod tests {
     #[test]
     fn test_sum_of_valid_middle_pages_example() {
         let cursor = Cursor::new(EXAMPLE_INPUT);
-        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor);
+        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor).unwrap();
 
         assert_eq!(sum_of_valid_middle_pages(&page_ordering_rules, &pages_to_produce), 143);
     }
@@ -162,7 +212,7 @@ mod tests {
     #[test]
     fn test_correct_page_order() {
         let cursor = Cursor::new(EXAMPLE_INPUT);
-        let (page_ordering_rules, _) = parse_pages(cursor);
+        let (page_ordering_rules, _) = parse_pages(cursor).unwrap();
 
         // Correct.
         let mut page = vec![75, 47, 61, 53, 29];
@@ -177,7 +227,7 @@ mod tests {
     #[test]
     fn test_sum_of_corrected_middle_pages_example() {
         let cursor = Cursor::new(EXAMPLE_INPUT);
-        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor);
+        let (page_ordering_rules, pages_to_produce) = parse_pages(cursor).unwrap();
 
         let sum_valid = sum_of_valid_middle_pages(&page_ordering_rules, &pages_to_produce);
 
@@ -188,4 +238,12 @@ mod tests {
 
         assert_eq!(sum_all - sum_valid, 123);
     }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day05::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "143");
+        assert_eq!(solution.part_two().value, "123");
+    }
 }
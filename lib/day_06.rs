@@ -1,143 +1,786 @@
-﻿use crate::char_map::CharMap;
+use crate::char_map::{ByteMap, CharMap};
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::render::{AnimationOptions, Canvas, Color, Renderer, Style};
+use crate::solution::{Answer, Solution};
+use std::{iter, thread};
 
-fn find_start_position(guard_map: &CharMap) -> (i32, i32) {
-    for y in 0..guard_map.height {
-        for x in 0..guard_map.width {
-            if guard_map.get(x, y) == '^' {
-                return (x, y);
-            }
-        }
-    }
-    return (-1, -1);
+/// Value returned for the positions outside the map.
+const OUTSIDE: char = '\0';
+
+fn find_start_position(guard_map: &CharMap) -> Point {
+    return guard_map.position(|&c| c == '^').unwrap_or(Point::new(-1, -1));
+}
+
+/// Returns the bit marking that the position was visited in the given direction.
+#[inline]
+fn direction_bit(direction: Direction) -> u8 {
+    1 << direction.index()
 }
 
 pub fn move_guard(guard_map: &mut CharMap) -> usize {
     let mut distinct_positions = 0;
-    let (mut x, mut y) = find_start_position(&guard_map);
-    let mut dir_x = 0;
-    let mut dir_y = -1;
-    while guard_map.get(x, y) != 0 as char {
-        if guard_map.get(x, y) != 'X' {
-            guard_map.set(x, y, 'X');
+    let mut position = find_start_position(guard_map);
+    let mut direction = Direction::Up;
+    while guard_map.get_or(position, OUTSIDE) != OUTSIDE {
+        if guard_map.get_or(position, OUTSIDE) != 'X' {
+            guard_map.set(position, 'X');
             distinct_positions += 1;
         }
-        let next_x = x + dir_x;
-        let next_y = y + dir_y;
-        if guard_map.get(next_x, next_y) != '#' {
-            x = next_x;
-            y = next_y;
+        let next = position + direction;
+        if guard_map.get_or(next, OUTSIDE) != '#' {
+            position = next;
         } else {
-            (dir_x, dir_y) = (-dir_y, dir_x);
+            direction = direction.turn_right();
         }
     }
     return distinct_positions;
 }
 
-fn move_guard_check_loop(guard_map: &mut CharMap, x: i32, y: i32, dir_x: i32, dir_y: i32, dir_bit: i32) -> bool {
-    let mut x = x;
-    let mut y = y;
+fn move_guard_check_loop(guard_map: &mut CharMap, position: Point, direction: Direction) -> bool {
+    let mut position = position;
+    let mut direction = direction;
 
-    let mut dir_x = dir_x;
-    let mut dir_y = dir_y;
-    let mut dir_bit = dir_bit;
-
-    while guard_map.get(x, y) != 0 as char {
-        let pos = guard_map.get(x, y);
+    while guard_map.get_or(position, OUTSIDE) != OUTSIDE {
+        let pos = guard_map.get_or(position, OUTSIDE);
         if pos == '.' {
-            guard_map.set(x, y, ((1 << dir_bit) as u8) as char);
+            guard_map.set(position, direction_bit(direction) as char);
         } else {
-            if (pos as u8) & (1 << dir_bit) != 0 {
+            if (pos as u8) & direction_bit(direction) != 0 {
                 return true;
             }
-            guard_map.set(x, y, (pos as u8 | (1 << dir_bit)) as char);
+            guard_map.set(position, (pos as u8 | direction_bit(direction)) as char);
         }
-        let next_x = x + dir_x;
-        let next_y = y + dir_y;
-        if guard_map.get(next_x, next_y) != '#' {
-            x = next_x;
-            y = next_y;
+        let next = position + direction;
+        if guard_map.get_or(next, OUTSIDE) != '#' {
+            position = next;
         } else {
-            (dir_x, dir_y) = (-dir_y, dir_x);
-            dir_bit = (dir_bit + 1) % 4;
+            direction = direction.turn_right();
         }
     }
 
     return false;
 }
-pub fn block_guard(guard_map: &mut CharMap) -> usize {
-    let mut obstruction_positions = 0;
-    let (mut x, mut y) = find_start_position(&guard_map);
+
+/// Returns the positions where an obstruction makes the guard walk in a loop, in the order the guard reaches them.
+pub fn block_guard_positions(guard_map: &mut CharMap) -> Vec<Point> {
+    let mut obstruction_positions = Vec::new();
+    let mut position = find_start_position(guard_map);
     // Clear the starting position to simplify the later checks.
-    guard_map.set(x, y, '.');
-    let mut dir_x = 0;
-    let mut dir_y = -1;
-    let mut dir_bit = 0;
-    while guard_map.get(x, y) != 0 as char {
-        let next_x = x + dir_x;
-        let next_y = y + dir_y;
-        if guard_map.get(next_x, next_y) == '.' {
+    guard_map.set(position, '.');
+    let mut direction = Direction::Up;
+    while guard_map.get_or(position, OUTSIDE) != OUTSIDE {
+        let next = position + direction;
+        if guard_map.get_or(next, OUTSIDE) == '.' {
             let mut guard_map_to_check = guard_map.clone();
-            guard_map_to_check.set(next_x, next_y, '#');
-            if move_guard_check_loop(&mut guard_map_to_check, x, y, dir_x, dir_y, dir_bit) {
-                obstruction_positions += 1;
+            guard_map_to_check.set(next, '#');
+            if move_guard_check_loop(&mut guard_map_to_check, position, direction) {
+                obstruction_positions.push(next);
             }
         }
-        let pos = guard_map.get(x, y);
+        let pos = guard_map.get_or(position, OUTSIDE);
         if pos == '.' {
-            guard_map.set(x, y, ((1 << dir_bit) as u8) as char);
+            guard_map.set(position, direction_bit(direction) as char);
         } else {
-            guard_map.set(x, y, (pos as u8 | (1 << dir_bit)) as char);
+            guard_map.set(position, (pos as u8 | direction_bit(direction)) as char);
         }
-        if guard_map.get(next_x, next_y) != '#' {
-            x = next_x;
-            y = next_y;
+        if guard_map.get_or(next, OUTSIDE) != '#' {
+            position = next;
         } else {
-            (dir_x, dir_y) = (-dir_y, dir_x);
-            dir_bit = (dir_bit + 1) % 4;
+            direction = direction.turn_right();
         }
     }
     return obstruction_positions;
 }
 
-pub fn block_guard_assign_map(guard_map: &mut CharMap) -> usize {
+pub fn block_guard(guard_map: &mut CharMap) -> usize {
+    block_guard_positions(guard_map).len()
+}
+
+pub fn block_guard_assign_map_positions(guard_map: &mut CharMap) -> Vec<Point> {
     // Create a copy of the guard map to check for obstructions.
     // We crate it once and reuse it to avoid allocating memory in the loop.
     let mut guard_map_to_check = guard_map.clone();
 
-    let mut obstruction_positions = 0;
-    let (mut x, mut y) = find_start_position(&guard_map);
+    let mut obstruction_positions = Vec::new();
+    let mut position = find_start_position(guard_map);
     // Clear the starting position to simplify the later checks.
-    guard_map.set(x, y, '.');
-    let mut dir_x = 0;
-    let mut dir_y = -1;
-    let mut dir_bit = 0;
-    while guard_map.get(x, y) != 0 as char {
-        let next_x = x + dir_x;
-        let next_y = y + dir_y;
-        if guard_map.get(next_x, next_y) == '.' {
-            guard_map_to_check.assign(&guard_map);
-            guard_map_to_check.set(next_x, next_y, '#');
-            if move_guard_check_loop(&mut guard_map_to_check, x, y, dir_x, dir_y, dir_bit) {
-                obstruction_positions += 1;
+    guard_map.set(position, '.');
+    let mut direction = Direction::Up;
+    while guard_map.get_or(position, OUTSIDE) != OUTSIDE {
+        let next = position + direction;
+        if guard_map.get_or(next, OUTSIDE) == '.' {
+            guard_map_to_check.clone_from(guard_map);
+            guard_map_to_check.set(next, '#');
+            if move_guard_check_loop(&mut guard_map_to_check, position, direction) {
+                obstruction_positions.push(next);
             }
         }
-        let pos = guard_map.get(x, y);
+        let pos = guard_map.get_or(position, OUTSIDE);
         if pos == '.' {
-            guard_map.set(x, y, ((1 << dir_bit) as u8) as char);
+            guard_map.set(position, direction_bit(direction) as char);
         } else {
-            guard_map.set(x, y, (pos as u8 | (1 << dir_bit)) as char);
+            guard_map.set(position, (pos as u8 | direction_bit(direction)) as char);
         }
-        if guard_map.get(next_x, next_y) != '#' {
-            x = next_x;
-            y = next_y;
+        if guard_map.get_or(next, OUTSIDE) != '#' {
+            position = next;
         } else {
-            (dir_x, dir_y) = (-dir_y, dir_x);
-            dir_bit = (dir_bit + 1) % 4;
+            direction = direction.turn_right();
         }
     }
     return obstruction_positions;
 }
 
+pub fn block_guard_assign_map(guard_map: &mut CharMap) -> usize {
+    block_guard_assign_map_positions(guard_map).len()
+}
+
+/// Returns the layer marking the positions visited by the guard before leaving the map.
+pub fn guard_path(guard_map: &ByteMap) -> Grid<bool> {
+    let mut visited = guard_map.layer(false);
+    let Some(GuardState { mut position, mut direction }) = find_guard(guard_map) else {
+        return visited;
+    };
+    while guard_map.contains(position) {
+        *visited.get_unchecked_mut(position.x as usize, position.y as usize) = true;
+        let next = position + direction;
+        if guard_map.get(next) != Some(&b'#') {
+            position = next;
+        } else {
+            direction = direction.turn_right();
+        }
+    }
+    visited
+}
+
+/// Same as `move_guard`, but keeps the visited positions in a side layer instead of the map.
+pub fn move_guard_layer(guard_map: &ByteMap) -> usize {
+    guard_path(guard_map).cells().filter(|(_, &visited)| visited).count()
+}
+
+/// Walks from the position until the guard leaves the map or loops. The map itself is not modified,
+/// the `obstruction` is an extra blocked position and the directions are marked in the `visited` layer.
+fn move_guard_check_loop_layer(guard_map: &ByteMap, visited: &mut Grid<u8>, position: Point, direction: Direction, obstruction: Point) -> bool {
+    let mut position = position;
+    let mut direction = direction;
+
+    loop {
+        let flags = visited.get_unchecked_mut(position.x as usize, position.y as usize);
+        if *flags & direction_bit(direction) != 0 {
+            return true;
+        }
+        *flags |= direction_bit(direction);
+
+        let next = position + direction;
+        match guard_map.get(next) {
+            None => return false,
+            Some(b'#') => direction = direction.turn_right(),
+            Some(_) if next == obstruction => direction = direction.turn_right(),
+            Some(_) => position = next,
+        }
+    }
+}
+
+/// Same as `block_guard_assign_map`, but the map stays untouched and the visited directions are kept
+/// in a byte layer, which is much cheaper to copy than the whole map of chars.
+pub fn block_guard_layer_positions(guard_map: &ByteMap) -> Vec<Point> {
+    let mut visited = guard_map.layer(0u8);
+    let mut visited_to_check = visited.clone();
+
+    let mut obstruction_positions = Vec::new();
+    let Some(GuardState { mut position, mut direction }) = find_guard(guard_map) else {
+        return obstruction_positions;
+    };
+    while let Some(&next_cell) = guard_map.get(position + direction) {
+        let next = position + direction;
+        // Obstructions can be placed only on the positions the guard has not walked through yet.
+        if next_cell != b'#' && *visited.get_unchecked(next.x as usize, next.y as usize) == 0 {
+            visited_to_check.clone_from(&visited);
+            if move_guard_check_loop_layer(guard_map, &mut visited_to_check, position, direction, next) {
+                obstruction_positions.push(next);
+            }
+        }
+        *visited.get_unchecked_mut(position.x as usize, position.y as usize) |= direction_bit(direction);
+        if next_cell != b'#' {
+            position = next;
+        } else {
+            direction = direction.turn_right();
+        }
+    }
+    obstruction_positions
+}
+
+pub fn block_guard_layer(guard_map: &ByteMap) -> usize {
+    block_guard_layer_positions(guard_map).len()
+}
+
+/// An obstruction to check, with the state of the guard in front of it and the step at which it was reached.
+#[derive(Clone, Copy)]
+struct Candidate {
+    step: u32,
+    guard: GuardState,
+    obstruction: Point,
+}
+
+/// Same as `move_guard_check_loop_layer`, but nothing has to be copied or cleared before the check.
+/// The states visited before reaching the obstruction are those whose step in `path_steps` is below the step
+/// of the candidate, the states visited during this check are those marked with `stamp` in the `scratch` layer.
+fn move_guard_check_loop_stamped(guard_map: &ByteMap, path_steps: &Grid<[u32; 4]>, scratch: &mut Grid<[u32; 4]>, stamp: u32, candidate: Candidate) -> bool {
+    let Candidate { step, guard, obstruction } = candidate;
+    let mut position = guard.position;
+    let mut direction = guard.direction;
+
+    loop {
+        let (x, y) = (position.x as usize, position.y as usize);
+        if path_steps.get_unchecked(x, y)[direction.index()] < step {
+            return true;
+        }
+        let stamps = scratch.get_unchecked_mut(x, y);
+        if stamps[direction.index()] == stamp {
+            return true;
+        }
+        stamps[direction.index()] = stamp;
+
+        let next = position + direction;
+        match guard_map.get(next) {
+            None => return false,
+            Some(b'#') => direction = direction.turn_right(),
+            Some(_) if next == obstruction => direction = direction.turn_right(),
+            Some(_) => position = next,
+        }
+    }
+}
+
+/// Walks the path once, returning the obstructions to check with the state of the guard in front of them,
+/// and the step at which each state of the path was reached.
+fn find_candidates(guard_map: &ByteMap) -> (Vec<Candidate>, Grid<[u32; 4]>) {
+    let mut candidates = Vec::new();
+    let mut path_steps = guard_map.layer([u32::MAX; 4]);
+    let mut step = 0;
+    let Some(GuardState { mut position, mut direction }) = find_guard(guard_map) else {
+        return (candidates, path_steps);
+    };
+    while let Some(&next_cell) = guard_map.get(position + direction) {
+        let next = position + direction;
+        // Obstructions can be placed only on the positions the guard has not walked through yet.
+        if next_cell != b'#' && path_steps.get_unchecked(next.x as usize, next.y as usize).iter().all(|&s| s == u32::MAX) {
+            candidates.push(Candidate {
+                step,
+                guard: GuardState { position, direction },
+                obstruction: next,
+            });
+        }
+        path_steps.get_unchecked_mut(position.x as usize, position.y as usize)[direction.index()] = step;
+        step += 1;
+        if next_cell != b'#' {
+            position = next;
+        } else {
+            direction = direction.turn_right();
+        }
+    }
+    (candidates, path_steps)
+}
+
+/// Same as `block_guard_layer_positions`, but the candidate obstructions are checked on up to `threads` threads,
+/// each with its own scratch layer. The path is walked once first, recording the candidates with the state of the guard
+/// in front of them and the step at which each state was reached, which the checks share.
+pub fn block_guard_parallel_positions(guard_map: &ByteMap, threads: usize) -> Vec<Point> {
+    let (candidates, path_steps) = find_candidates(guard_map);
+
+    // Consecutive chunks keep the positions in the order of the sequential search.
+    let chunk_size = candidates.len().div_ceil(threads.max(1)).max(1);
+    let path_steps = &path_steps;
+    thread::scope(|scope| {
+        let workers: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut scratch = guard_map.layer([0u32; 4]);
+                    let mut positions = Vec::new();
+                    for (stamp, &candidate) in (1..).zip(chunk) {
+                        if move_guard_check_loop_stamped(guard_map, path_steps, &mut scratch, stamp, candidate) {
+                            positions.push(candidate.obstruction);
+                        }
+                    }
+                    positions
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    })
+}
+
+pub fn block_guard_parallel(guard_map: &ByteMap, threads: usize) -> usize {
+    block_guard_parallel_positions(guard_map, threads).len()
+}
+
+/// For each cell and direction, the cell where the guard walking from there stops in front of an obstacle,
+/// or `None` when the guard leaves the map. The guard can then jump from obstacle to obstacle.
+pub struct JumpTable {
+    stops: Grid<[Option<Point>; 4]>,
+}
+
+impl JumpTable {
+    pub fn new(guard_map: &ByteMap) -> JumpTable {
+        let mut stops = guard_map.layer([None; 4]);
+        let points: Vec<Point> = guard_map.cells().map(|(point, _)| point).collect();
+        for direction in Direction::ALL {
+            // The cell in front of each cell has to be resolved first.
+            let order: Box<dyn Iterator<Item = &Point>> = match direction {
+                Direction::Up | Direction::Left => Box::new(points.iter()),
+                Direction::Right | Direction::Down => Box::new(points.iter().rev()),
+            };
+            for &point in order {
+                let ahead = point + direction;
+                let stop = match guard_map.get(ahead) {
+                    None => None,
+                    Some(b'#') => Some(point),
+                    Some(_) => stops.get_unchecked(ahead.x as usize, ahead.y as usize)[direction.index()],
+                };
+                stops.get_unchecked_mut(point.x as usize, point.y as usize)[direction.index()] = stop;
+            }
+        }
+        JumpTable { stops }
+    }
+
+    /// Returns where the guard stops walking from the position, taking the extra obstruction into account.
+    pub fn jump(&self, position: Point, direction: Direction, obstruction: Point) -> Option<Point> {
+        let stop = self.stops.get_unchecked(position.x as usize, position.y as usize)[direction.index()];
+        // The table does not know about the obstruction, it matters only when it lies between the position and the stop.
+        let offset = obstruction - position;
+        let ahead = match direction {
+            Direction::Up => offset.x == 0 && offset.y < 0,
+            Direction::Right => offset.y == 0 && offset.x > 0,
+            Direction::Down => offset.x == 0 && offset.y > 0,
+            Direction::Left => offset.y == 0 && offset.x < 0,
+        };
+        if ahead && stop.is_none_or(|stop| position.manhattan_distance(obstruction) <= position.manhattan_distance(stop)) {
+            Some(obstruction - direction)
+        } else {
+            stop
+        }
+    }
+
+    /// Same as `move_guard_check_loop_stamped`, but the guard jumps between obstacles and only the states
+    /// after the turns are marked in the `scratch` layer.
+    fn check_loop(&self, scratch: &mut Grid<[u32; 4]>, stamp: u32, guard: GuardState, obstruction: Point) -> bool {
+        let mut position = guard.position;
+        let mut direction = guard.direction;
+        while let Some(stop) = self.jump(position, direction, obstruction) {
+            position = stop;
+            direction = direction.turn_right();
+            let stamps = scratch.get_unchecked_mut(position.x as usize, position.y as usize);
+            if stamps[direction.index()] == stamp {
+                return true;
+            }
+            stamps[direction.index()] = stamp;
+        }
+        false
+    }
+}
+
+/// Same as `block_guard_layer_positions`, but the loops are checked with a `JumpTable`,
+/// so a check takes time proportional to the number of turns instead of the number of steps.
+pub fn block_guard_jump_positions(guard_map: &ByteMap) -> Vec<Point> {
+    let (candidates, _) = find_candidates(guard_map);
+    let table = JumpTable::new(guard_map);
+    let mut scratch = guard_map.layer([0u32; 4]);
+    (1..)
+        .zip(candidates)
+        .filter(|&(stamp, candidate)| table.check_loop(&mut scratch, stamp, candidate.guard, candidate.obstruction))
+        .map(|(_, candidate)| candidate.obstruction)
+        .collect()
+}
+
+pub fn block_guard_jump(guard_map: &ByteMap) -> usize {
+    block_guard_jump_positions(guard_map).len()
+}
+
+/// Position and direction of the guard at one step of the patrol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuardState {
+    pub position: Point,
+    pub direction: Direction,
+}
+
+/// Which way the guard turns in front of an obstacle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Right,
+    Left,
+    Back,
+}
+
+impl Turn {
+    pub fn apply(self, direction: Direction) -> Direction {
+        match self {
+            Turn::Right => direction.turn_right(),
+            Turn::Left => direction.turn_left(),
+            Turn::Back => direction.opposite(),
+        }
+    }
+}
+
+/// What happens when the guard walks over the edge of the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    /// The guard leaves the map and the patrol ends.
+    Exit,
+    /// The guard continues from the opposite edge.
+    Wrap,
+}
+
+/// How the guard reacts to the tile in front of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    /// The guard steps on the tile.
+    Open,
+    /// The guard turns in place, according to the `Turn` of the rules.
+    Obstacle,
+    /// The guard steps on the tile and is moved to the given position, keeping its direction.
+    /// The patrol ends when the position is outside the map.
+    Teleport(Point),
+    /// The guard steps on the tile and faces the given direction.
+    Redirect(Direction),
+}
+
+/// Rules of the patrol, the default ones are those of the puzzle: the guard turns right in front of '#' and leaves at the edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatrolRules {
+    pub turn: Turn,
+    pub edges: Edges,
+    /// Behaviour of each tile, indexed by its byte.
+    pub tiles: [Tile; 256],
+}
+
+impl PatrolRules {
+    pub const PUZZLE: PatrolRules = {
+        let mut tiles = [Tile::Open; 256];
+        tiles[b'#' as usize] = Tile::Obstacle;
+        PatrolRules {
+            turn: Turn::Right,
+            edges: Edges::Exit,
+            tiles,
+        }
+    };
+
+    pub fn with_turn(self, turn: Turn) -> PatrolRules {
+        PatrolRules { turn, ..self }
+    }
+
+    pub fn with_edges(self, edges: Edges) -> PatrolRules {
+        PatrolRules { edges, ..self }
+    }
+
+    pub fn with_tile(mut self, glyph: u8, tile: Tile) -> PatrolRules {
+        self.tiles[glyph as usize] = tile;
+        self
+    }
+
+    /// Returns the state after the next move or turn of the guard, `None` when the guard leaves the map.
+    /// The `obstruction` is an extra obstacle.
+    pub fn step(&self, guard_map: &ByteMap, state: GuardState, obstruction: Option<Point>) -> Option<GuardState> {
+        let mut ahead = state.position + state.direction;
+        if !guard_map.contains(ahead) {
+            match self.edges {
+                Edges::Exit => return None,
+                Edges::Wrap => {
+                    let (width, height) = (guard_map.width() as i32, guard_map.height() as i32);
+                    ahead = Point::new(ahead.x.rem_euclid(width), ahead.y.rem_euclid(height));
+                }
+            }
+        }
+
+        let tile = if Some(ahead) == obstruction {
+            Tile::Obstacle
+        } else {
+            self.tiles[*guard_map.get_unchecked(ahead.x as usize, ahead.y as usize) as usize]
+        };
+        match tile {
+            Tile::Open => Some(GuardState { position: ahead, ..state }),
+            Tile::Obstacle => Some(GuardState {
+                direction: self.turn.apply(state.direction),
+                ..state
+            }),
+            Tile::Teleport(target) => guard_map.contains(target).then_some(GuardState { position: target, ..state }),
+            Tile::Redirect(direction) => Some(GuardState { position: ahead, direction }),
+        }
+    }
+}
+
+impl Default for PatrolRules {
+    fn default() -> PatrolRules {
+        PatrolRules::PUZZLE
+    }
+}
+
+/// Returns the guards on the map, marked by '^', '>', 'v' or '<' depending on the direction they face.
+pub fn find_guards(guard_map: &ByteMap) -> Vec<GuardState> {
+    guard_map
+        .cells()
+        .filter_map(|(position, &c)| {
+            let direction = Direction::ALL.into_iter().find(|&direction| guard_glyph(direction) as u8 == c)?;
+            Some(GuardState { position, direction })
+        })
+        .collect()
+}
+
+/// Returns the first guard on the map, see `find_guards`.
+fn find_guard(guard_map: &ByteMap) -> Option<GuardState> {
+    find_guards(guard_map).first().copied()
+}
+
+/// Steps through the patrol of the guard, yielding the state after each move or turn, starting with the initial one.
+/// The iteration ends when the guard leaves the map or would repeat a state, see `Patrol::looped`.
+pub struct Patrol<'a> {
+    guard_map: &'a ByteMap,
+    rules: &'a PatrolRules,
+    obstruction: Option<Point>,
+    next: Option<GuardState>,
+    /// Directions in which the guard went through each position so far.
+    visited: Grid<u8>,
+    looped: bool,
+}
+
+impl<'a> Patrol<'a> {
+    /// Patrols the map with the puzzle rules, starting from the first guard on the map.
+    pub fn new(guard_map: &'a ByteMap) -> Patrol<'a> {
+        Patrol::with_rules(guard_map, &PatrolRules::PUZZLE, find_guard(guard_map), None)
+    }
+
+    /// Patrols the map with the rules from the given state of the guard, `None` gives an empty patrol.
+    pub fn with_rules(guard_map: &'a ByteMap, rules: &'a PatrolRules, guard: Option<GuardState>, obstruction: Option<Point>) -> Patrol<'a> {
+        Patrol {
+            guard_map,
+            rules,
+            obstruction,
+            next: guard,
+            visited: guard_map.layer(0),
+            looped: false,
+        }
+    }
+
+    /// Patrols the map with an extra obstruction at the given position.
+    pub fn with_obstruction(guard_map: &'a ByteMap, obstruction: Point) -> Patrol<'a> {
+        Patrol {
+            obstruction: Some(obstruction),
+            ..Patrol::new(guard_map)
+        }
+    }
+
+    pub fn is_visited(&self, position: Point) -> bool {
+        self.visited.get(position).is_some_and(|&directions| directions != 0)
+    }
+
+    /// Returns the positions visited so far.
+    pub fn visited(&self) -> impl Iterator<Item = Point> + '_ {
+        self.visited.cells().filter(|(_, &directions)| directions != 0).map(|(point, _)| point)
+    }
+
+    /// Whether the patrol ended because the guard walks in a loop.
+    pub fn looped(&self) -> bool {
+        self.looped
+    }
+
+    /// Returns the first state the guard would repeat, where the loop starts, if the guard walks in a loop.
+    pub fn repeated_state(&self) -> Option<GuardState> {
+        self.next.filter(|_| self.looped)
+    }
+}
+
+impl Iterator for Patrol<'_> {
+    type Item = GuardState;
+
+    fn next(&mut self) -> Option<GuardState> {
+        let state = self.next.take()?;
+        let directions = self.visited.get_unchecked_mut(state.position.x as usize, state.position.y as usize);
+        if *directions & direction_bit(state.direction) != 0 {
+            // Kept for `repeated_state`, the iteration still ends here.
+            self.looped = true;
+            self.next = Some(state);
+            return None;
+        }
+        *directions |= direction_bit(state.direction);
+
+        self.next = self.rules.step(self.guard_map, state, self.obstruction);
+        Some(state)
+    }
+}
+
+/// Returns the states of the loop the guard walks in with an extra obstruction, starting with the first repeated one.
+/// Returns `None` when the guard leaves the map instead.
+pub fn guard_loop(guard_map: &ByteMap, obstruction: Point) -> Option<Vec<GuardState>> {
+    let mut patrol = Patrol::with_obstruction(guard_map, obstruction);
+    let mut states: Vec<GuardState> = patrol.by_ref().collect();
+    let start = patrol.repeated_state()?;
+    let loop_start = states.iter().position(|&state| state == start)?;
+    Some(states.split_off(loop_start))
+}
+
+/// An obstruction making the guard walk in a loop, with the states of the loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardLoop {
+    pub obstruction: Point,
+    pub path: Vec<GuardState>,
+}
+
+/// Returns all obstructions making the guard walk in a loop, in the order the guard reaches them.
+pub fn guard_loops(guard_map: &ByteMap) -> Vec<GuardLoop> {
+    block_guard_layer_positions(guard_map)
+        .into_iter()
+        .filter_map(|obstruction| guard_loop(guard_map, obstruction).map(|path| GuardLoop { obstruction, path }))
+        .collect()
+}
+
+/// Returns the number of distinct positions visited by any of the guards, each patrolling alone.
+/// With a single guard and the puzzle rules, this is the answer of `move_guard`.
+pub fn patrol_positions(guard_map: &ByteMap, rules: &PatrolRules) -> usize {
+    let mut visited = guard_map.layer(false);
+    for guard in find_guards(guard_map) {
+        let mut patrol = Patrol::with_rules(guard_map, rules, Some(guard), None);
+        patrol.by_ref().for_each(drop);
+        patrol.visited().for_each(|point| _ = visited.set(point, true));
+    }
+    visited.cells().filter(|(_, &visited)| visited).count()
+}
+
+/// Returns the positions where an obstruction makes at least one of the guards walk in a loop, in reading order.
+/// The obstruction can be placed on any position a guard walks through, except the starting ones.
+/// With a single guard and the puzzle rules, these are the positions of `block_guard`.
+pub fn patrol_obstructions(guard_map: &ByteMap, rules: &PatrolRules) -> Vec<Point> {
+    let guards = find_guards(guard_map);
+    let mut candidates = guard_map.layer(false);
+    for &guard in &guards {
+        let mut patrol = Patrol::with_rules(guard_map, rules, Some(guard), None);
+        patrol.by_ref().for_each(drop);
+        patrol.visited().for_each(|point| _ = candidates.set(point, true));
+    }
+    guards.iter().for_each(|guard| _ = candidates.set(guard.position, false));
+
+    candidates
+        .cells()
+        .filter(|(_, &candidate)| candidate)
+        .map(|(point, _)| point)
+        .filter(|&obstruction| {
+            guards.iter().any(|&guard| {
+                let mut patrol = Patrol::with_rules(guard_map, rules, Some(guard), Some(obstruction));
+                patrol.by_ref().for_each(drop);
+                patrol.looped()
+            })
+        })
+        .collect()
+}
+
+fn guard_glyph(direction: Direction) -> char {
+    match direction {
+        Direction::Up => '^',
+        Direction::Right => '>',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+    }
+}
+
+fn patrol_frame(guard_map: &ByteMap, patrol: &Patrol, guard: Option<GuardState>) -> Canvas {
+    Renderer::new(guard_map)
+        .style(|_, &c| if c == b'#' { Style::fg(Color::BrightBlack) } else { Style::NONE })
+        .overlay(patrol.visited(), Some('X'), Style::fg(Color::Yellow))
+        .overlay(patrol.obstruction, Some('O'), Style::fg(Color::BrightCyan).bold())
+        .overlay(
+            guard.map(|guard| guard.position),
+            guard.map(|guard| guard_glyph(guard.direction)),
+            Style::fg(Color::Red).bold(),
+        )
+        .canvas()
+}
+
+/// Renders every `options.step`-th state of the patrol and always the last one. With `options.candidates`,
+/// one more frame follows for each obstruction looping the guard, showing the loop.
+pub fn patrol_frames(guard_map: &ByteMap, options: AnimationOptions) -> impl Iterator<Item = Canvas> + '_ {
+    let mut patrol = Patrol::new(guard_map);
+    let mut steps = 0;
+    let mut last = None;
+    let mut last_rendered = true;
+    let walk = iter::from_fn(move || {
+        for state in patrol.by_ref() {
+            last = Some(state);
+            last_rendered = steps % options.step.max(1) == 0;
+            steps += 1;
+            if last_rendered {
+                return Some(patrol_frame(guard_map, &patrol, last));
+            }
+        }
+        if last_rendered {
+            return None;
+        }
+        last_rendered = true;
+        Some(patrol_frame(guard_map, &patrol, last))
+    });
+
+    let candidates = if options.candidates { block_guard_layer_positions(guard_map) } else { Vec::new() };
+    let loops = candidates.into_iter().map(move |obstruction| {
+        let mut patrol = Patrol::with_obstruction(guard_map, obstruction);
+        let last = patrol.by_ref().last();
+        patrol_frame(guard_map, &patrol, last)
+    });
+
+    walk.chain(loops)
+}
+
+#[derive(Default)]
+pub struct Day06 {
+    guard_map: ByteMap,
+    /// The single guard on the map, checked when parsing.
+    guard: Option<GuardState>,
+}
+
+impl Solution for Day06 {
+    fn day(&self) -> u32 {
+        6
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        let guard_map = ByteMap::parse_ascii(input)?;
+        match find_guards(&guard_map)[..] {
+            [] => return Err(Error::parse(1, 1, "expected a guard")),
+            [guard] => self.guard = Some(guard),
+            [_, second, ..] => {
+                let Point { x, y } = second.position;
+                return Err(Error::parse(y as usize + 1, x as usize + 1, "expected a single guard, found another one"));
+            }
+        }
+        self.guard_map = guard_map;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The guard visited {} distinct positions", move_guard_layer(&self.guard_map))
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new("The guard can be looped with {} obstruction positions", block_guard_jump(&self.guard_map))
+    }
+
+    fn visualize(&self) -> Option<Canvas> {
+        let path = guard_path(&self.guard_map);
+        let path_points = path.cells().filter(|(_, &visited)| visited).map(|(point, _)| point);
+        let renderer = Renderer::new(&self.guard_map)
+            .style(|_, &c| if c == b'#' { Style::fg(Color::BrightBlack) } else { Style::NONE })
+            .overlay(path_points, Some('X'), Style::fg(Color::Yellow))
+            .overlay(block_guard_jump_positions(&self.guard_map), Some('O'), Style::fg(Color::BrightCyan).bold())
+            .overlay(
+                self.guard.map(|guard| guard.position),
+                self.guard.map(|guard| guard_glyph(guard.direction)),
+                Style::fg(Color::Red).bold(),
+            );
+        Some(renderer.canvas())
+    }
+
+    fn animate(&self, options: AnimationOptions) -> Option<Box<dyn Iterator<Item = Canvas> + '_>> {
+        Some(Box::new(patrol_frames(&self.guard_map, options)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,37 +806,301 @@ mod tests {
 
     #[test]
     fn test_find_start_position() {
-        let guard_map = CharMap::new(&INPUT.to_string());
-        let (x, y) = find_start_position(&guard_map);
-        assert_eq!(x, 2);
-        assert_eq!(y, 2);
+        let guard_map = CharMap::parse(INPUT).unwrap();
+        assert_eq!(find_start_position(&guard_map), Point::new(2, 2));
     }
 
     #[test]
     fn test_move_guard() {
-        let mut guard_map = CharMap::new(&INPUT.to_string());
+        let mut guard_map = CharMap::parse(INPUT).unwrap();
         let distinct_positions = move_guard(&mut guard_map);
         assert_eq!(distinct_positions, 12);
     }
 
     #[test]
     fn test_move_guard_example() {
-        let mut guard_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let mut guard_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let distinct_positions = move_guard(&mut guard_map);
         assert_eq!(distinct_positions, 41);
     }
 
     #[test]
     fn test_block_guard_example() {
-        let mut guard_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let mut guard_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let obstruction_positions = block_guard(&mut guard_map);
         assert_eq!(obstruction_positions, 6);
     }
 
     #[test]
     fn test_block_guard_assign_map_example() {
-        let mut guard_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let mut guard_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let obstruction_positions = block_guard_assign_map(&mut guard_map);
         assert_eq!(obstruction_positions, 6);
     }
+
+    #[test]
+    fn test_move_guard_layer_example() {
+        let guard_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        assert_eq!(move_guard_layer(&guard_map), 41);
+        assert_eq!(move_guard_layer(&ByteMap::parse_ascii(INPUT).unwrap()), 12);
+    }
+
+    #[test]
+    fn test_block_guard_layer_example() {
+        let guard_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        assert_eq!(block_guard_layer(&guard_map), 6);
+    }
+
+    #[test]
+    fn test_visualize() {
+        let mut solution = Day06::default();
+        solution.parse(INPUT).unwrap();
+        let expected = r#".O#...
+.XXXX#
+.X^.X.
+#OXXX.
+....#.
+"#;
+        assert_eq!(solution.visualize().unwrap().to_string(), expected);
+
+        // A guard facing another way keeps its glyph.
+        solution.parse("..#\n.<.\n").unwrap();
+        assert_eq!(solution.visualize().unwrap().to_string(), "..#\nX<.\n");
+    }
+
+    #[test]
+    fn test_block_guard_positions() {
+        let mut expected = vec![Point::new(3, 6), Point::new(6, 7), Point::new(7, 7), Point::new(1, 8), Point::new(3, 8), Point::new(7, 9)];
+        expected.sort();
+
+        let mut positions = block_guard_positions(&mut CharMap::parse(EXAMPLE_INPUT).unwrap());
+        positions.sort();
+        assert_eq!(positions, expected);
+        let mut positions = block_guard_assign_map_positions(&mut CharMap::parse(EXAMPLE_INPUT).unwrap());
+        positions.sort();
+        assert_eq!(positions, expected);
+        let mut positions = block_guard_layer_positions(&ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap());
+        positions.sort();
+        assert_eq!(positions, expected);
+    }
+
+    #[test]
+    fn test_block_guard_parallel() {
+        let guard_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        let expected = block_guard_layer_positions(&guard_map);
+        for threads in [0, 1, 2, 4, 100] {
+            assert_eq!(block_guard_parallel_positions(&guard_map, threads), expected);
+        }
+        assert_eq!(block_guard_parallel(&guard_map, 3), 6);
+        assert_eq!(block_guard_parallel(&ByteMap::parse_ascii(INPUT).unwrap(), 2), 2);
+    }
+
+    #[test]
+    fn test_jump_table() {
+        let guard_map = ByteMap::parse_ascii(INPUT).unwrap();
+        let table = JumpTable::new(&guard_map);
+        let nowhere = Point::new(-1, -1);
+        assert_eq!(table.jump(Point::new(2, 2), Direction::Up, nowhere), Some(Point::new(2, 1)));
+        assert_eq!(table.jump(Point::new(2, 1), Direction::Right, nowhere), Some(Point::new(4, 1)));
+        assert_eq!(table.jump(Point::new(4, 1), Direction::Down, nowhere), Some(Point::new(4, 3)));
+        assert_eq!(table.jump(Point::new(0, 4), Direction::Right, nowhere), Some(Point::new(3, 4)));
+        assert_eq!(table.jump(Point::new(1, 3), Direction::Left, nowhere), Some(Point::new(1, 3)));
+        assert_eq!(table.jump(Point::new(1, 1), Direction::Up, nowhere), None);
+        assert_eq!(table.jump(Point::new(5, 4), Direction::Down, nowhere), None);
+
+        // The obstruction counts only when it is in front of the guard, before the next obstacle.
+        assert_eq!(table.jump(Point::new(2, 1), Direction::Right, Point::new(3, 1)), Some(Point::new(2, 1)));
+        assert_eq!(table.jump(Point::new(2, 1), Direction::Right, Point::new(1, 1)), Some(Point::new(4, 1)));
+        assert_eq!(table.jump(Point::new(0, 4), Direction::Right, Point::new(5, 4)), Some(Point::new(3, 4)));
+        assert_eq!(table.jump(Point::new(1, 1), Direction::Up, Point::new(1, 0)), Some(Point::new(1, 1)));
+        assert_eq!(table.jump(Point::new(5, 4), Direction::Down, Point::new(4, 4)), None);
+    }
+
+    #[test]
+    fn test_block_guard_jump() {
+        let guard_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        assert_eq!(block_guard_jump_positions(&guard_map), block_guard_layer_positions(&guard_map));
+        assert_eq!(block_guard_jump(&ByteMap::parse_ascii(INPUT).unwrap()), 2);
+    }
+
+    #[test]
+    fn test_patrol_rules_puzzle() {
+        for input in [INPUT, EXAMPLE_INPUT] {
+            let guard_map = ByteMap::parse_ascii(input).unwrap();
+            let rules = PatrolRules::default();
+            assert_eq!(patrol_positions(&guard_map, &rules), move_guard_layer(&guard_map));
+            let mut expected = block_guard_layer_positions(&guard_map);
+            expected.sort_by_key(|point| (point.y, point.x));
+            assert_eq!(patrol_obstructions(&guard_map, &rules), expected);
+        }
+    }
+
+    #[test]
+    fn test_find_guards() {
+        let guard_map = ByteMap::parse_ascii("^.>\n.v.\n<#.\n").unwrap();
+        let guard = |x, y, direction| GuardState {
+            position: Point::new(x, y),
+            direction,
+        };
+        assert_eq!(
+            find_guards(&guard_map),
+            [
+                guard(0, 0, Direction::Up),
+                guard(2, 0, Direction::Right),
+                guard(1, 1, Direction::Down),
+                guard(0, 2, Direction::Left)
+            ]
+        );
+        assert!(find_guards(&ByteMap::parse_ascii("..\n").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_patrol_rules() {
+        let guard_map = ByteMap::parse_ascii("...#\n....\n>..#\n").unwrap();
+        let guard = find_guards(&guard_map).first().copied();
+        let positions = |rules: &PatrolRules| Patrol::with_rules(&guard_map, rules, guard, None).map(|state| state.position).collect::<Vec<Point>>();
+        let points = |points: &[(i32, i32)]| points.iter().map(|&point| Point::from(point)).collect::<Vec<Point>>();
+
+        // Right turn at (2, 2), then down and out.
+        assert_eq!(positions(&PatrolRules::PUZZLE), points(&[(0, 2), (1, 2), (2, 2), (2, 2)]));
+        // Left turn, then up to the wall at (3, 0) which is not in the way.
+        assert_eq!(
+            positions(&PatrolRules::PUZZLE.with_turn(Turn::Left)),
+            points(&[(0, 2), (1, 2), (2, 2), (2, 2), (2, 1), (2, 0)])
+        );
+        assert_eq!(
+            positions(&PatrolRules::PUZZLE.with_turn(Turn::Back)),
+            points(&[(0, 2), (1, 2), (2, 2), (2, 2), (1, 2), (0, 2)])
+        );
+
+        // Walking back and forth between the wall and the wall over the edge is a loop.
+        let rules = PatrolRules::PUZZLE.with_turn(Turn::Back).with_edges(Edges::Wrap);
+        let mut patrol = Patrol::with_rules(&guard_map, &rules, guard, None);
+        assert_eq!(patrol.by_ref().count(), 6);
+        assert!(patrol.looped());
+
+        let rules = PatrolRules::PUZZLE.with_tile(b'#', Tile::Teleport(Point::new(1, 1)));
+        assert_eq!(positions(&rules), points(&[(0, 2), (1, 2), (2, 2), (1, 1), (2, 1), (3, 1)]));
+        let rules = PatrolRules::PUZZLE.with_tile(b'#', Tile::Teleport(Point::new(9, 9)));
+        assert_eq!(positions(&rules), points(&[(0, 2), (1, 2), (2, 2)]));
+        let rules = PatrolRules::PUZZLE.with_tile(b'.', Tile::Redirect(Direction::Up));
+        assert_eq!(positions(&rules), points(&[(0, 2), (1, 2), (1, 1), (1, 0)]));
+    }
+
+    #[test]
+    fn test_patrol_several_guards() {
+        let guard_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        let mut two_guards = guard_map.clone();
+        two_guards.set(Point::new(9, 9), b'>');
+        assert_eq!(find_guards(&two_guards).len(), 2);
+        // The second guard leaves right away, it does not change the obstructions.
+        assert_eq!(patrol_positions(&two_guards, &PatrolRules::PUZZLE), 41 + 1);
+        assert_eq!(
+            patrol_obstructions(&two_guards, &PatrolRules::PUZZLE),
+            patrol_obstructions(&guard_map, &PatrolRules::PUZZLE)
+        );
+
+        // The first guard walks in a loop on its own, the second one leaves at the bottom.
+        let guard_map = ByteMap::parse_ascii(".#...\n...#.\n.....\n#^.v.\n..#..\n").unwrap();
+        assert_eq!(patrol_positions(&guard_map, &PatrolRules::PUZZLE), 6 + 2);
+    }
+
+    #[test]
+    fn test_guard_loops() {
+        let guard_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        let loops = guard_loops(&guard_map);
+        assert_eq!(loops.len(), 6);
+        for guard_loop in &loops {
+            let path = &guard_loop.path;
+            // Each state follows the previous one by a move or a turn, and the last one leads back to the first.
+            for (i, state) in path.iter().enumerate() {
+                let next = path[(i + 1) % path.len()];
+                let moved = next.direction == state.direction && next.position == state.position + state.direction;
+                let turned = next.direction == state.direction.turn_right() && next.position == state.position;
+                assert!(moved || turned);
+                if turned {
+                    let ahead = state.position + state.direction;
+                    assert!(ahead == guard_loop.obstruction || guard_map.get(ahead) == Some(&b'#'));
+                }
+            }
+            assert!(path.iter().any(|state| state.position + state.direction == guard_loop.obstruction));
+        }
+
+        assert_eq!(guard_loop(&guard_map, Point::new(0, 0)), None);
+    }
+
+    #[test]
+    fn test_patrol() {
+        let guard_map = ByteMap::parse_ascii(INPUT).unwrap();
+        let mut patrol = Patrol::new(&guard_map);
+        let states: Vec<GuardState> = patrol.by_ref().collect();
+        assert_eq!(
+            states[..3],
+            [
+                GuardState {
+                    position: Point::new(2, 2),
+                    direction: Direction::Up
+                },
+                GuardState {
+                    position: Point::new(2, 1),
+                    direction: Direction::Up
+                },
+                GuardState {
+                    position: Point::new(2, 1),
+                    direction: Direction::Right
+                },
+            ]
+        );
+        assert!(!patrol.looped());
+        assert_eq!(patrol.visited().count(), move_guard_layer(&guard_map));
+        assert!(patrol.is_visited(Point::new(1, 1)) && !patrol.is_visited(Point::new(0, 0)));
+
+        let example_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        let mut patrol = Patrol::with_obstruction(&example_map, Point::new(3, 6));
+        assert!(patrol.by_ref().count() > 0 && patrol.looped());
+        let mut patrol = Patrol::with_obstruction(&example_map, Point::new(0, 0));
+        assert!(patrol.by_ref().count() > 0 && !patrol.looped());
+
+        assert_eq!(Patrol::new(&ByteMap::parse_ascii("..\n").unwrap()).count(), 0);
+    }
+
+    #[test]
+    fn test_patrol_frames() {
+        let guard_map = ByteMap::parse_ascii(INPUT).unwrap();
+        let steps = Patrol::new(&guard_map).count();
+        assert_eq!(patrol_frames(&guard_map, AnimationOptions::default()).count(), steps);
+        // Every 5th step and the last one.
+        let expected = steps.div_ceil(5) + usize::from(!(steps - 1).is_multiple_of(5));
+        assert_eq!(patrol_frames(&guard_map, AnimationOptions { step: 5, candidates: false }).count(), expected);
+
+        let frames: Vec<Canvas> = patrol_frames(&guard_map, AnimationOptions { step: 100, candidates: false }).collect();
+        assert_eq!(frames[0].to_string(), "..#...\n.....#\n..^...\n#.....\n....#.\n");
+        assert_eq!(frames[1].to_string(), ".^#...\n.XXXX#\n.XX.X.\n#XXXX.\n....#.\n");
+
+        let example_map = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        let options = AnimationOptions {
+            step: usize::MAX,
+            candidates: true,
+        };
+        let frames: Vec<Canvas> = patrol_frames(&example_map, options).collect();
+        assert_eq!(frames.len(), 2 + block_guard_layer(&example_map));
+        assert!(frames[2..].iter().all(|frame| frame.to_string().contains('O')));
+    }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day06::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "41");
+        assert_eq!(solution.part_two().value, "6");
+    }
+
+    #[test]
+    fn test_solution_parse_errors() {
+        let mut solution = Day06::default();
+        let error = solution.parse("..#\n...\n").unwrap_err();
+        assert_eq!(error.to_string(), "1:1: expected a guard");
+        let error = solution.parse("^.#\n..<\n").unwrap_err();
+        assert_eq!(error.to_string(), "2:3: expected a single guard, found another one");
+    }
 }
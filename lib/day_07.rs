@@ -1,5 +1,8 @@
-﻿use std::io::BufRead;
+﻿use crate::error::{column_of, parse_number, Error, Result};
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
+#[derive(Debug)]
 pub struct Equation {
     value: i64,
     operands: Vec<i32>,
@@ -11,22 +14,37 @@ impl Equation {
     }
 }
 
-pub fn parse_equations<R: BufRead>(reader: R) -> Vec<Equation> {
+pub fn parse_equations<R: BufRead>(reader: R) -> Result<Vec<Equation>> {
     let mut equations = Vec::new();
 
-    for line in reader.lines() {
-        let line = line.unwrap();
-        let mut parts = line.split(":");
-        let value_part = parts.next().unwrap().trim();
-        let operands_part = parts.next().unwrap().trim();
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_idx + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (value_part, operands_part) = match line.split_once(':') {
+            Some(parts) => parts,
+            None => return Err(Error::parse(line_number, line.chars().count() + 1, "expected ':'")),
+        };
 
-        let value = value_part.parse::<i64>().unwrap();
-        let operands: Vec<i32> = operands_part.split(" ").map(|x| x.parse::<i32>().unwrap()).collect();
+        let value = parse_number(&line, line_number, value_part.trim())?;
+        let operands = operands_part
+            .split_whitespace()
+            .map(|x| parse_number(&line, line_number, x))
+            .collect::<Result<Vec<i32>>>()?;
+        if operands.is_empty() {
+            return Err(Error::parse(
+                line_number,
+                column_of(&line, operands_part) + operands_part.chars().count(),
+                "expected operands",
+            ));
+        }
 
         equations.push(Equation::new(value, operands));
     }
 
-    return equations;
+    return Ok(equations);
 }
 
 pub fn can_calibrate_equation(equation: &Equation) -> bool {
@@ -141,6 +159,30 @@ pub fn sum_can_calibrate_values_concat(equations: &Vec<Equation>) -> i64 {
     return sum;
 }
 
+#[derive(Default)]
+pub struct Day07 {
+    equations: Vec<Equation>,
+}
+
+impl Solution for Day07 {
+    fn day(&self) -> u32 {
+        7
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.equations = parse_equations(input.as_bytes())?;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The total calibration result is {}", sum_can_calibrate_values(&self.equations))
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new("The total calibration result with concatenation is {}", sum_can_calibrate_values_concat(&self.equations))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,7 +203,7 @@ mod tests {
     fn test_parse_equations() {
         let data = "190: 10 19\n3267: 81 40 27\n";
         let cursor = Cursor::new(data);
-        let equations = parse_equations(cursor);
+        let equations = parse_equations(cursor).unwrap();
 
         assert_eq!(equations.len(), 2);
         assert_eq!(equations[0].value, 190);
@@ -170,6 +212,22 @@ mod tests {
         assert_eq!(equations[1].operands, vec![81, 40, 27]);
     }
 
+    #[test]
+    fn test_parse_equations_errors() {
+        let error = parse_equations(Cursor::new("190: 10 19\n3267 81 40 27\n")).unwrap_err();
+        assert_eq!(error.to_string(), "2:14: expected ':'");
+
+        let error = parse_equations(Cursor::new("190: 10 1x\n")).unwrap_err();
+        assert_eq!(error.to_string(), "1:9: invalid number '1x'");
+
+        let error = parse_equations(Cursor::new("190:\n")).unwrap_err();
+        assert_eq!(error.to_string(), "1:5: expected operands");
+
+        // The column counts the characters, not the bytes of the ideographic space.
+        let error = parse_equations(Cursor::new("190:\u{3000}\n")).unwrap_err();
+        assert_eq!(error.to_string(), "1:6: expected operands");
+    }
+
     #[test]
     fn test_can_calibrate_equation_example() {
         // 190: 10 19
@@ -235,14 +293,22 @@ mod tests {
     #[test]
     fn test_sum_can_calibrate_values_example() {
         let cursor = Cursor::new(EXAMPLE_DATA);
-        let equations = parse_equations(cursor);
+        let equations = parse_equations(cursor).unwrap();
         assert_eq!(sum_can_calibrate_values(&equations), 3749);
     }
 
     #[test]
     fn test_sum_can_calibrate_values_concat_example() {
         let cursor = Cursor::new(EXAMPLE_DATA);
-        let equations = parse_equations(cursor);
+        let equations = parse_equations(cursor).unwrap();
         assert_eq!(sum_can_calibrate_values_concat(&equations), 11387);
     }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day07::default();
+        solution.parse(EXAMPLE_DATA).unwrap();
+        assert_eq!(solution.part_one().value, "3749");
+        assert_eq!(solution.part_two().value, "11387");
+    }
 }
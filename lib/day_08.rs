@@ -1,45 +1,44 @@
 ﻿use crate::char_map::CharMap;
-use std::collections::HashSet;
-
-pub fn gather_antennas(antennas_map: &CharMap) -> std::collections::HashMap<char, Vec<(i32, i32)>> {
-    let mut antennas: std::collections::HashMap<char, Vec<(i32, i32)>> = std::collections::HashMap::new();
-
-    for y in 0..antennas_map.height {
-        for x in 0..antennas_map.width {
-            let pos = antennas_map.get(x, y);
-            if pos != '.' {
-                let antenna = antennas.entry(pos).or_insert(Vec::new());
-                antenna.push((x, y));
-            }
+use crate::error::Result;
+use crate::point::Point;
+use crate::render::{Canvas, Color, Renderer, Style};
+use crate::solution::{Answer, Solution};
+use std::collections::{HashMap, HashSet};
+
+/// Positions of the antennas, grouped by their frequency.
+pub type Antennas = HashMap<char, Vec<Point>>;
+
+pub fn gather_antennas(antennas_map: &CharMap) -> Antennas {
+    let mut antennas = Antennas::new();
+
+    for (position, &pos) in antennas_map.cells() {
+        if pos != '.' {
+            let antenna = antennas.entry(pos).or_default();
+            antenna.push(position);
         }
     }
 
     return antennas;
 }
 
-pub fn find_antinodes(antennas: &std::collections::HashMap<char, Vec<(i32, i32)>>, antennas_map: &CharMap) -> Vec<(i32, i32)> {
+pub fn find_antinodes(antennas: &Antennas, antennas_map: &CharMap) -> Vec<Point> {
     let mut antinodes = Vec::new();
 
-    #[inline]
-    fn is_in_bounds(pos: &(i32, i32), antennas_map: &CharMap) -> bool {
-        return pos.0 >= 0 && pos.0 < antennas_map.width && pos.1 >= 0 && pos.1 < antennas_map.height;
-    }
-
     for antenna in antennas.values() {
         for i in 0..antenna.len() - 1 {
             for j in i + 1..antenna.len() {
                 let antenna1 = antenna[i];
                 let antenna2 = antenna[j];
 
-                let offset = (antenna2.0 - antenna1.0, antenna2.1 - antenna1.1);
+                let offset = antenna2 - antenna1;
 
-                let antinode1 = (antenna1.0 - offset.0, antenna1.1 - offset.1);
-                let antinode2 = (antenna2.0 + offset.0, antenna2.1 + offset.1);
+                let antinode1 = antenna1 - offset;
+                let antinode2 = antenna2 + offset;
 
-                if is_in_bounds(&antinode1, &antennas_map) && !antinodes.contains(&antinode1) {
+                if antennas_map.contains(antinode1) && !antinodes.contains(&antinode1) {
                     antinodes.push(antinode1);
                 }
-                if is_in_bounds(&antinode2, &antennas_map) && !antinodes.contains(&antinode2) {
+                if antennas_map.contains(antinode2) && !antinodes.contains(&antinode2) {
                     antinodes.push(antinode2);
                 }
             }
@@ -49,29 +48,24 @@ pub fn find_antinodes(antennas: &std::collections::HashMap<char, Vec<(i32, i32)>
     return antinodes;
 }
 
-pub fn find_antinodes_sort_dedup(antennas: &std::collections::HashMap<char, Vec<(i32, i32)>>, antennas_map: &CharMap) -> Vec<(i32, i32)> {
+pub fn find_antinodes_sort_dedup(antennas: &Antennas, antennas_map: &CharMap) -> Vec<Point> {
     let mut antinodes = Vec::new();
 
-    #[inline]
-    fn is_in_bounds(pos: &(i32, i32), antennas_map: &CharMap) -> bool {
-        return pos.0 >= 0 && pos.0 < antennas_map.width && pos.1 >= 0 && pos.1 < antennas_map.height;
-    }
-
     for antenna in antennas.values() {
         for i in 0..antenna.len() - 1 {
             for j in i + 1..antenna.len() {
                 let antenna1 = antenna[i];
                 let antenna2 = antenna[j];
 
-                let offset = (antenna2.0 - antenna1.0, antenna2.1 - antenna1.1);
+                let offset = antenna2 - antenna1;
 
-                let antinode1 = (antenna1.0 - offset.0, antenna1.1 - offset.1);
-                let antinode2 = (antenna2.0 + offset.0, antenna2.1 + offset.1);
+                let antinode1 = antenna1 - offset;
+                let antinode2 = antenna2 + offset;
 
-                if is_in_bounds(&antinode1, &antennas_map) {
+                if antennas_map.contains(antinode1) {
                     antinodes.push(antinode1);
                 }
-                if is_in_bounds(&antinode2, &antennas_map) {
+                if antennas_map.contains(antinode2) {
                     antinodes.push(antinode2);
                 }
             }
@@ -83,29 +77,24 @@ pub fn find_antinodes_sort_dedup(antennas: &std::collections::HashMap<char, Vec<
     return antinodes;
 }
 
-pub fn find_antinodes_hash_set(antennas: &std::collections::HashMap<char, Vec<(i32, i32)>>, antennas_map: &CharMap) -> Vec<(i32, i32)> {
+pub fn find_antinodes_hash_set(antennas: &Antennas, antennas_map: &CharMap) -> Vec<Point> {
     let mut antinodes = HashSet::new();
 
-    #[inline]
-    fn is_in_bounds(pos: &(i32, i32), antennas_map: &CharMap) -> bool {
-        return pos.0 >= 0 && pos.0 < antennas_map.width && pos.1 >= 0 && pos.1 < antennas_map.height;
-    }
-
     for antenna in antennas.values() {
         for i in 0..antenna.len() - 1 {
             for j in i + 1..antenna.len() {
                 let antenna1 = antenna[i];
                 let antenna2 = antenna[j];
 
-                let offset = (antenna2.0 - antenna1.0, antenna2.1 - antenna1.1);
+                let offset = antenna2 - antenna1;
 
-                let antinode1 = (antenna1.0 - offset.0, antenna1.1 - offset.1);
-                let antinode2 = (antenna2.0 + offset.0, antenna2.1 + offset.1);
+                let antinode1 = antenna1 - offset;
+                let antinode2 = antenna2 + offset;
 
-                if is_in_bounds(&antinode1, &antennas_map) {
+                if antennas_map.contains(antinode1) {
                     antinodes.insert(antinode1);
                 }
-                if is_in_bounds(&antinode2, &antennas_map) {
+                if antennas_map.contains(antinode2) {
                     antinodes.insert(antinode2);
                 }
             }
@@ -124,14 +113,9 @@ fn gcd(mut a: i32, mut b: i32) -> i32 {
     return a.abs();
 }
 
-pub fn find_antinodes_in_line(antennas: &std::collections::HashMap<char, Vec<(i32, i32)>>, antennas_map: &CharMap) -> Vec<(i32, i32)> {
+pub fn find_antinodes_in_line(antennas: &Antennas, antennas_map: &CharMap) -> Vec<Point> {
     let mut antinodes = Vec::new();
 
-    #[inline]
-    fn is_in_bounds(pos: &(i32, i32), antennas_map: &CharMap) -> bool {
-        return pos.0 >= 0 && pos.0 < antennas_map.width && pos.1 >= 0 && pos.1 < antennas_map.height;
-    }
-
     for antenna in antennas.values() {
         for i in 0..antenna.len() - 1 {
             for j in i + 1..antenna.len() {
@@ -145,12 +129,12 @@ pub fn find_antinodes_in_line(antennas: &std::collections::HashMap<char, Vec<(i3
                     antinodes.push(antenna2);
                 }
 
-                let offset = (antenna2.0 - antenna1.0, antenna2.1 - antenna1.1);
-                let count = gcd(offset.0, offset.1);
-                let offset = (offset.0 / count, offset.1 / count);
+                let offset = antenna2 - antenna1;
+                let count = gcd(offset.x, offset.y);
+                let offset = Point::new(offset.x / count, offset.y / count);
 
                 for n in 1..count {
-                    let antinode = (antenna1.0 + offset.0 * n, antenna1.1 + offset.1 * n);
+                    let antinode = antenna1 + offset * n;
                     if !antinodes.contains(&antinode) {
                         antinodes.push(antinode);
                     }
@@ -158,8 +142,8 @@ pub fn find_antinodes_in_line(antennas: &std::collections::HashMap<char, Vec<(i3
 
                 let mut n = 1;
                 loop {
-                    let antinode = (antenna1.0 - offset.0 * n, antenna1.1 - offset.1 * n);
-                    if !is_in_bounds(&antinode, &antennas_map) {
+                    let antinode = antenna1 - offset * n;
+                    if !antennas_map.contains(antinode) {
                         break;
                     }
                     if !antinodes.contains(&antinode) {
@@ -170,8 +154,8 @@ pub fn find_antinodes_in_line(antennas: &std::collections::HashMap<char, Vec<(i3
 
                 let mut n = 1;
                 loop {
-                    let antinode = (antenna2.0 + offset.0 * n, antenna2.1 + offset.1 * n);
-                    if !is_in_bounds(&antinode, &antennas_map) {
+                    let antinode = antenna2 + offset * n;
+                    if !antennas_map.contains(antinode) {
                         break;
                     }
                     if !antinodes.contains(&antinode) {
@@ -186,14 +170,9 @@ pub fn find_antinodes_in_line(antennas: &std::collections::HashMap<char, Vec<(i3
     return antinodes;
 }
 
-pub fn find_antinodes_in_line_sort_dedup(antennas: &std::collections::HashMap<char, Vec<(i32, i32)>>, antennas_map: &CharMap) -> Vec<(i32, i32)> {
+pub fn find_antinodes_in_line_sort_dedup(antennas: &Antennas, antennas_map: &CharMap) -> Vec<Point> {
     let mut antinodes = Vec::new();
 
-    #[inline]
-    fn is_in_bounds(pos: &(i32, i32), antennas_map: &CharMap) -> bool {
-        return pos.0 >= 0 && pos.0 < antennas_map.width && pos.1 >= 0 && pos.1 < antennas_map.height;
-    }
-
     for antenna in antennas.values() {
         for i in 0..antenna.len() - 1 {
             for j in i + 1..antenna.len() {
@@ -203,19 +182,19 @@ pub fn find_antinodes_in_line_sort_dedup(antennas: &std::collections::HashMap<ch
                 antinodes.push(antenna1);
                 antinodes.push(antenna2);
 
-                let offset = (antenna2.0 - antenna1.0, antenna2.1 - antenna1.1);
-                let count = gcd(offset.0, offset.1);
-                let offset = (offset.0 / count, offset.1 / count);
+                let offset = antenna2 - antenna1;
+                let count = gcd(offset.x, offset.y);
+                let offset = Point::new(offset.x / count, offset.y / count);
 
                 for n in 1..count {
-                    let antinode = (antenna1.0 + offset.0 * n, antenna1.1 + offset.1 * n);
+                    let antinode = antenna1 + offset * n;
                     antinodes.push(antinode);
                 }
 
                 let mut n = 1;
                 loop {
-                    let antinode = (antenna1.0 - offset.0 * n, antenna1.1 - offset.1 * n);
-                    if !is_in_bounds(&antinode, &antennas_map) {
+                    let antinode = antenna1 - offset * n;
+                    if !antennas_map.contains(antinode) {
                         break;
                     }
                     antinodes.push(antinode);
@@ -224,8 +203,8 @@ pub fn find_antinodes_in_line_sort_dedup(antennas: &std::collections::HashMap<ch
 
                 let mut n = 1;
                 loop {
-                    let antinode = (antenna2.0 + offset.0 * n, antenna2.1 + offset.1 * n);
-                    if !is_in_bounds(&antinode, &antennas_map) {
+                    let antinode = antenna2 + offset * n;
+                    if !antennas_map.contains(antinode) {
                         break;
                     }
                     antinodes.push(antinode);
@@ -240,14 +219,9 @@ pub fn find_antinodes_in_line_sort_dedup(antennas: &std::collections::HashMap<ch
     return antinodes;
 }
 
-pub fn find_antinodes_in_line_hash_set(antennas: &std::collections::HashMap<char, Vec<(i32, i32)>>, antennas_map: &CharMap) -> Vec<(i32, i32)> {
+pub fn find_antinodes_in_line_hash_set(antennas: &Antennas, antennas_map: &CharMap) -> Vec<Point> {
     let mut antinodes = HashSet::new();
 
-    #[inline]
-    fn is_in_bounds(pos: &(i32, i32), antennas_map: &CharMap) -> bool {
-        return pos.0 >= 0 && pos.0 < antennas_map.width && pos.1 >= 0 && pos.1 < antennas_map.height;
-    }
-
     for antenna in antennas.values() {
         for i in 0..antenna.len() - 1 {
             for j in i + 1..antenna.len() {
@@ -257,19 +231,19 @@ pub fn find_antinodes_in_line_hash_set(antennas: &std::collections::HashMap<char
                 antinodes.insert(antenna1);
                 antinodes.insert(antenna2);
 
-                let offset = (antenna2.0 - antenna1.0, antenna2.1 - antenna1.1);
-                let count = gcd(offset.0, offset.1);
-                let offset = (offset.0 / count, offset.1 / count);
+                let offset = antenna2 - antenna1;
+                let count = gcd(offset.x, offset.y);
+                let offset = Point::new(offset.x / count, offset.y / count);
 
                 for n in 1..count {
-                    let antinode = (antenna1.0 + offset.0 * n, antenna1.1 + offset.1 * n);
+                    let antinode = antenna1 + offset * n;
                     antinodes.insert(antinode);
                 }
 
                 let mut n = 1;
                 loop {
-                    let antinode = (antenna1.0 - offset.0 * n, antenna1.1 - offset.1 * n);
-                    if !is_in_bounds(&antinode, &antennas_map) {
+                    let antinode = antenna1 - offset * n;
+                    if !antennas_map.contains(antinode) {
                         break;
                     }
                     antinodes.insert(antinode);
@@ -278,8 +252,8 @@ pub fn find_antinodes_in_line_hash_set(antennas: &std::collections::HashMap<char
 
                 let mut n = 1;
                 loop {
-                    let antinode = (antenna2.0 + offset.0 * n, antenna2.1 + offset.1 * n);
-                    if !is_in_bounds(&antinode, &antennas_map) {
+                    let antinode = antenna2 + offset * n;
+                    if !antennas_map.contains(antinode) {
                         break;
                     }
                     antinodes.insert(antinode);
@@ -292,6 +266,47 @@ pub fn find_antinodes_in_line_hash_set(antennas: &std::collections::HashMap<char
     return antinodes.iter().copied().collect();
 }
 
+#[derive(Default)]
+pub struct Day08 {
+    antennas_map: CharMap,
+    antennas: Antennas,
+}
+
+impl Solution for Day08 {
+    fn day(&self) -> u32 {
+        8
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.antennas_map = CharMap::parse(input)?;
+        self.antennas = gather_antennas(&self.antennas_map);
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        let antinodes = find_antinodes_sort_dedup(&self.antennas, &self.antennas_map);
+        Answer::new("The map contains {} unique antinode locations", antinodes.len())
+    }
+
+    fn part_two(&self) -> Answer {
+        let antinodes_in_line = find_antinodes_in_line_sort_dedup(&self.antennas, &self.antennas_map);
+        Answer::new("The map contains {} unique antinode locations in line", antinodes_in_line.len())
+    }
+
+    /// Antinodes of the first part are drawn as `#`, the additional ones of the second part as `*`.
+    /// Antinodes on the antennas keep the antenna character.
+    fn visualize(&self) -> Option<Canvas> {
+        let is_empty = |point: &Point| self.antennas_map.get(*point) == Some(&'.');
+        let antinodes = find_antinodes_sort_dedup(&self.antennas, &self.antennas_map);
+        let antinodes_in_line = find_antinodes_in_line_sort_dedup(&self.antennas, &self.antennas_map);
+        let renderer = Renderer::new(&self.antennas_map)
+            .style(|_, &c| if c != '.' { Style::fg(Color::Cyan).bold() } else { Style::NONE })
+            .overlay(antinodes_in_line.into_iter().filter(is_empty), Some('*'), Style::fg(Color::Yellow))
+            .overlay(antinodes.into_iter().filter(is_empty), Some('#'), Style::fg(Color::Red).bold());
+        Some(renderer.canvas())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,7 +327,7 @@ mod tests {
 
     #[test]
     fn test_find_start_position() {
-        let antennas_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let antennas_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
 
         let antennas = gather_antennas(&antennas_map);
 
@@ -323,7 +338,7 @@ mod tests {
 
     #[test]
     fn test_find_antinodes() {
-        let antennas_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let antennas_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let antennas = gather_antennas(&antennas_map);
         let antinodes = find_antinodes(&antennas, &antennas_map);
 
@@ -332,7 +347,7 @@ mod tests {
 
     #[test]
     fn test_find_antinodes_sort_dedup() {
-        let antennas_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let antennas_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let antennas = gather_antennas(&antennas_map);
         let antinodes = find_antinodes_sort_dedup(&antennas, &antennas_map);
 
@@ -341,7 +356,7 @@ mod tests {
 
     #[test]
     fn test_find_antinodes_hash_set() {
-        let antennas_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let antennas_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let antennas = gather_antennas(&antennas_map);
         let antinodes = find_antinodes_hash_set(&antennas, &antennas_map);
 
@@ -350,7 +365,7 @@ mod tests {
 
     #[test]
     fn test_find_antinodes_in_line() {
-        let antennas_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let antennas_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let antennas = gather_antennas(&antennas_map);
         let antinodes = find_antinodes_in_line(&antennas, &antennas_map);
 
@@ -359,7 +374,7 @@ mod tests {
 
     #[test]
     fn test_find_antinodes_in_line_sort_dedup() {
-        let antennas_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let antennas_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let antennas = gather_antennas(&antennas_map);
         let antinodes = find_antinodes_in_line_sort_dedup(&antennas, &antennas_map);
 
@@ -368,10 +383,29 @@ mod tests {
 
     #[test]
     fn test_find_antinodes_in_line_hash_set() {
-        let antennas_map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let antennas_map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let antennas = gather_antennas(&antennas_map);
         let antinodes = find_antinodes_in_line_hash_set(&antennas, &antennas_map);
 
         assert_eq!(antinodes.len(), 34);
     }
+
+    #[test]
+    fn test_visualize() {
+        let mut solution = Day08::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        let map = solution.visualize().unwrap().to_string();
+        // One of the 14 antinodes is on an antenna.
+        assert_eq!(map.matches('#').count(), 13);
+        assert_eq!(map.matches('#').count() + map.matches('*').count(), 34 - 7);
+        assert_eq!(map.lines().next(), Some("**....#....#"));
+    }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day08::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "14");
+        assert_eq!(solution.part_two().value, "34");
+    }
 }
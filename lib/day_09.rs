@@ -1,4 +1,20 @@
-﻿pub fn calculate_filesystem_checksum(disk_map: &Vec<char>) -> usize {
+﻿use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+pub fn parse_disk_map(input: &str) -> Result<Vec<char>> {
+    let input = input.trim_end();
+    for (idx, c) in input.chars().enumerate() {
+        if !c.is_ascii_digit() {
+            return Err(Error::parse(1, idx + 1, format!("expected digit, found '{}'", c)));
+        }
+    }
+    if input.is_empty() {
+        return Err(Error::parse(1, 1, "expected disk map"));
+    }
+    Ok(input.chars().collect())
+}
+
+pub fn calculate_filesystem_checksum(disk_map: &Vec<char>) -> usize {
     #[inline]
     fn get_disk_len(disk_map: &Vec<char>, idx: usize) -> usize {
         let c = disk_map[idx];
@@ -264,12 +280,46 @@ pub fn calculate_filesystem_checksum_v2_optimized(disk_map: &Vec<char>) -> usize
     return checksum;
 }
 
+#[derive(Default)]
+pub struct Day09 {
+    disk_map: Vec<char>,
+}
+
+impl Solution for Day09 {
+    fn day(&self) -> u32 {
+        9
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.disk_map = parse_disk_map(input)?;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The checksum of the filesystem is {}", calculate_filesystem_checksum(&self.disk_map))
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new(
+            "After moving whole files the checksum of the filesystem is {}",
+            calculate_filesystem_checksum_v2_optimized(&self.disk_map),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     const EXAMPLE_INPUT: &str = "2333133121414131402";
 
+    #[test]
+    fn test_parse_disk_map() {
+        assert_eq!(parse_disk_map("2333\n").unwrap(), vec!['2', '3', '3', '3']);
+        assert_eq!(parse_disk_map("23x3").unwrap_err().to_string(), "1:3: expected digit, found 'x'");
+        assert_eq!(parse_disk_map("").unwrap_err().to_string(), "1:1: expected disk map");
+    }
+
     #[test]
     fn test_calculate_filesystem_checksum_example() {
         let input = EXAMPLE_INPUT.to_string().chars().collect();
@@ -287,4 +337,12 @@ mod tests {
         let input = EXAMPLE_INPUT.to_string().chars().collect();
         assert_eq!(calculate_filesystem_checksum_v2_optimized(&input), 2858);
     }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day09::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "1928");
+        assert_eq!(solution.part_two().value, "2858");
+    }
 }
@@ -1,11 +1,12 @@
 ﻿use crate::char_map::CharMap;
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::render::{Canvas, Color, Renderer, Style};
+use crate::solution::{Answer, Solution};
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Pos(i32, i32);
-
-const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-fn find_tailhead_score(map: &CharMap, start: Pos) -> usize {
-    assert_eq!(map.get(start.0, start.1), '0');
+fn find_tailhead_score(map: &CharMap, start: Point) -> usize {
+    assert_eq!(map.get(start), Some(&'0'));
 
     let mut points_to_visit = vec![start];
     let mut char_to_check = '1';
@@ -13,9 +14,8 @@ fn find_tailhead_score(map: &CharMap, start: Pos) -> usize {
     while !points_to_visit.is_empty() && char_to_check != last_char {
         let mut next_points = Vec::new();
         for point in points_to_visit {
-            for dir in DIRECTIONS.iter() {
-                let next = Pos(point.0 + dir.0, point.1 + dir.1);
-                if map.get(next.0, next.1) == char_to_check {
+            for next in point.neighbours() {
+                if map.get(next) == Some(&char_to_check) {
                     next_points.push(next);
                 }
             }
@@ -37,29 +37,26 @@ fn find_tailhead_score(map: &CharMap, start: Pos) -> usize {
 
 pub fn calculate_sum_of_tailhead_scores(map: &CharMap) -> usize {
     let mut sum = 0;
-    for y in 0..map.height {
-        for x in 0..map.width {
-            if map.get(x, y) == '0' {
-                sum += find_tailhead_score(map, Pos(x, y));
-            }
+    for (point, &height) in map.cells() {
+        if height == '0' {
+            sum += find_tailhead_score(map, point);
         }
     }
     return sum;
 }
 
-fn find_tailhead_rating(map: &CharMap, start: Pos) -> usize {
-    assert_eq!(map.get(start.0, start.1), '0');
+fn find_tailhead_rating(map: &CharMap, start: Point) -> usize {
+    assert_eq!(map.get(start), Some(&'0'));
 
     return find_tailhead_rating_recursive(&map, start, '1');
 }
 
-fn find_tailhead_rating_recursive(map: &CharMap, point: Pos, char_to_check: char) -> usize {
+fn find_tailhead_rating_recursive(map: &CharMap, point: Point, char_to_check: char) -> usize {
     let mut rating = 0;
 
     let next_char_to_check = (char_to_check as u8 + 1) as char;
-    for dir in DIRECTIONS.iter() {
-        let next_point = Pos(point.0 + dir.0, point.1 + dir.1);
-        if map.get(next_point.0, next_point.1) == char_to_check {
+    for next_point in point.neighbours() {
+        if map.get(next_point) == Some(&char_to_check) {
             if char_to_check == '9' {
                 rating += 1;
             } else {
@@ -73,16 +70,76 @@ fn find_tailhead_rating_recursive(map: &CharMap, point: Pos, char_to_check: char
 
 pub fn calculate_sum_of_tailhead_ratings(map: &CharMap) -> usize {
     let mut sum = 0;
-    for y in 0..map.height {
-        for x in 0..map.width {
-            if map.get(x, y) == '0' {
-                sum += find_tailhead_rating(map, Pos(x, y));
-            }
+    for (point, &height) in map.cells() {
+        if height == '0' {
+            sum += find_tailhead_rating(map, point);
         }
     }
     return sum;
 }
 
+/// Returns the layer marking the positions lying on any hiking trail,
+/// i.e. reachable from a trailhead and leading to a position of height 9.
+pub fn trail_positions(map: &CharMap) -> Grid<bool> {
+    // Going down from the height 9 marks the positions leading to the top.
+    let mut leads_to_top = map.layer(false);
+    for height in ('0'..='9').rev() {
+        let higher = (height as u8 + 1) as char;
+        for (point, &c) in map.cells() {
+            if c == height && (height == '9' || point.neighbours().any(|n| map.get(n) == Some(&higher) && leads_to_top.get(n) == Some(&true))) {
+                leads_to_top.set(point, true);
+            }
+        }
+    }
+
+    // Going up from the trailheads keeps just the positions reachable from them.
+    let mut on_trail = map.layer(false);
+    for height in '0'..='9' {
+        let lower = (height as u8 - 1) as char;
+        for (point, &c) in map.cells() {
+            if c == height && leads_to_top.get(point) == Some(&true) && (height == '0' || point.neighbours().any(|n| map.get(n) == Some(&lower) && on_trail.get(n) == Some(&true)))
+            {
+                on_trail.set(point, true);
+            }
+        }
+    }
+    on_trail
+}
+
+#[derive(Default)]
+pub struct Day10 {
+    topographic_map: CharMap,
+}
+
+impl Solution for Day10 {
+    fn day(&self) -> u32 {
+        10
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.topographic_map = CharMap::parse(input)?;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The sum of tailhead scores is {}", calculate_sum_of_tailhead_scores(&self.topographic_map))
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new("The sum of tailhead ratings is {}", calculate_sum_of_tailhead_ratings(&self.topographic_map))
+    }
+
+    /// Shows just the positions on the hiking trails, the trailheads are highlighted.
+    fn visualize(&self) -> Option<Canvas> {
+        let on_trail = trail_positions(&self.topographic_map);
+        let off_trail = on_trail.cells().filter(|(_, &on)| !on).map(|(point, _)| point);
+        let renderer = Renderer::new(&self.topographic_map)
+            .style(|_, &c| if c == '0' { Style::fg(Color::Red).bold() } else { Style::fg(Color::Green) })
+            .overlay(off_trail, Some('.'), Style::fg(Color::BrightBlack));
+        Some(renderer.canvas())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,27 +161,58 @@ mod tests {
 8765
 9876
 "#;
-        let map = CharMap::new(&input.to_string());
+        let map = CharMap::parse(input).unwrap();
 
-        assert_eq!(find_tailhead_score(&map, Pos(0, 0)), 1);
+        assert_eq!(find_tailhead_score(&map, Point::new(0, 0)), 1);
     }
 
     #[test]
     fn test_calculate_sum_of_tailhead_scores_example() {
-        let map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         assert_eq!(calculate_sum_of_tailhead_scores(&map), 36);
     }
 
     #[test]
     fn test_find_tailhead_rating() {
-        let map = CharMap::new(&EXAMPLE_INPUT.to_string());
-        assert_eq!(find_tailhead_rating(&map, Pos(2, 0)), 20);
-        assert_eq!(find_tailhead_rating(&map, Pos(4, 0)), 24);
+        let map = CharMap::parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(find_tailhead_rating(&map, Point::new(2, 0)), 20);
+        assert_eq!(find_tailhead_rating(&map, Point::new(4, 0)), 24);
     }
 
     #[test]
     fn test_calculate_sum_of_tailhead_ratings_example() {
-        let map = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let map = CharMap::parse(EXAMPLE_INPUT).unwrap();
         assert_eq!(calculate_sum_of_tailhead_ratings(&map), 81);
     }
+
+    #[test]
+    fn test_visualize() {
+        let input = r#"1110111
+1111111
+1112111
+6543456
+7111117
+8111118
+9111119
+"#;
+        let expected = r#"...0...
+...1...
+...2...
+6543456
+7.....7
+8.....8
+9.....9
+"#;
+        let mut solution = Day10::default();
+        solution.parse(input).unwrap();
+        assert_eq!(solution.visualize().unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day10::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "36");
+        assert_eq!(solution.part_two().value, "81");
+    }
 }
@@ -1,5 +1,14 @@
-﻿pub fn parse_stones(input: &String) -> Vec<u64> {
-    input.split_whitespace().map(|x| x.parse::<u64>().unwrap()).collect()
+﻿use crate::error::{parse_number, Result};
+use crate::solution::{Answer, Solution};
+
+pub fn parse_stones(input: &String) -> Result<Vec<u64>> {
+    let mut stones = Vec::new();
+    for (line_idx, line) in input.lines().enumerate() {
+        for x in line.split_whitespace() {
+            stones.push(parse_number(line, line_idx + 1, x)?);
+        }
+    }
+    Ok(stones)
 }
 
 fn num_decimal_digits(value: u64) -> u32 {
@@ -93,6 +102,36 @@ pub fn calculate_number_of_stones_after_blinks_cached(stones: &Vec<u64>, blinks:
     return total_stones;
 }
 
+#[derive(Default)]
+pub struct Day11 {
+    stones: Vec<u64>,
+}
+
+impl Solution for Day11 {
+    fn day(&self) -> u32 {
+        11
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.stones = parse_stones(&input.to_string())?;
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new(
+            "The number of stones after 25 blinks is {}",
+            calculate_number_of_stones_after_blinks_cached(&self.stones, 25),
+        )
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new(
+            "The number of stones after 75 blinks is {}",
+            calculate_number_of_stones_after_blinks_cached(&self.stones, 75),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,7 +140,13 @@ mod tests {
 
     #[test]
     fn test_parse_input() {
-        assert_eq!(parse_stones(&EXAMPLE_INPUT.to_string()), vec![125, 17]);
+        assert_eq!(parse_stones(&EXAMPLE_INPUT.to_string()).unwrap(), vec![125, 17]);
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        let error = parse_stones(&"125 17 -1".to_string()).unwrap_err();
+        assert_eq!(error.to_string(), "1:8: invalid number '-1'");
     }
 
     #[test]
@@ -124,15 +169,22 @@ mod tests {
 
     #[test]
     fn test_calculate_number_of_stones_after_blinks_example() {
-        let stones = parse_stones(&EXAMPLE_INPUT.to_string());
+        let stones = parse_stones(&EXAMPLE_INPUT.to_string()).unwrap();
         assert_eq!(calculate_number_of_stones_after_blinks(&stones, 6), 22);
         assert_eq!(calculate_number_of_stones_after_blinks(&stones, 25), 55312);
     }
 
     #[test]
     fn test_calculate_number_of_stones_after_blinks_cached_example() {
-        let stones = parse_stones(&EXAMPLE_INPUT.to_string());
+        let stones = parse_stones(&EXAMPLE_INPUT.to_string()).unwrap();
         assert_eq!(calculate_number_of_stones_after_blinks_cached(&stones, 6), 22);
         assert_eq!(calculate_number_of_stones_after_blinks_cached(&stones, 25), 55312);
     }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day11::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "55312");
+    }
 }
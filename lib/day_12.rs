@@ -1,12 +1,10 @@
-﻿use crate::char_map::CharMap;
-use crate::matrix::Matrix;
-
-const TOP: (i32, i32) = (0, -1);
-const LEFT: (i32, i32) = (-1, 0);
-const RIGHT: (i32, i32) = (1, 0);
-const BOTTOM: (i32, i32) = (0, 1);
-
-const DIRECTIONS: [(i32, i32); 4] = [TOP, LEFT, BOTTOM, RIGHT];
+﻿use crate::char_map::ByteMap;
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::render::{Canvas, Color, Renderer, Style};
+use crate::solution::{Answer, Solution};
+use std::sync::OnceLock;
 
 struct Fence {
     area: i32,
@@ -14,42 +12,82 @@ struct Fence {
     corners: i32,
 }
 
-pub fn calculate_fencing_price(garden: &CharMap) -> (i32, i32) {
-    let mut garden_regions = Matrix::new(garden.width as usize, garden.height as usize, 0u32);
+/// Returns the perimeter and the number of corners of the plot.
+#[inline]
+fn plot_fence<T: Copy + PartialEq>(garden: &Grid<T>, plot_pos: Point, plot: T, outside: T) -> (i32, i32) {
+    let mut perimeter = 0;
+    let mut corners = 0;
+    for d1 in Direction::ALL {
+        let d2 = d1.turn_right();
+        if garden.get_or(plot_pos + d1, outside) != plot {
+            perimeter += 1;
+        }
+        let outer = garden.get_or(plot_pos + d1, outside) != plot && garden.get_or(plot_pos + d2, outside) != plot;
+        let inner = garden.get_or(plot_pos + d1, outside) == plot && garden.get_or(plot_pos + d2, outside) == plot && garden.get_or(plot_pos + d1 + d2, outside) != plot;
+        if outer || inner {
+            corners += 1;
+        }
+    }
+    (perimeter, corners)
+}
+
+/// Same as `plot_fence`, but each neighbour is compared only once.
+#[inline]
+fn plot_fence_neighbours<T: Copy + PartialEq>(garden: &Grid<T>, plot_pos: Point, plot: T, outside: T) -> (i32, i32) {
+    let same = Direction::ALL.map(|direction| garden.get_or(plot_pos + direction, outside) == plot);
+
+    let mut perimeter = 0;
+    let mut corners = 0;
+    for i in 0..4 {
+        let j = (i + 1) % 4;
+        if !same[i] {
+            perimeter += 1;
+        }
+        // Either an outer corner, or an inner one with the diagonal plot from another region.
+        let outer = !same[i] && !same[j];
+        if outer || (same[i] && same[j] && garden.get_or(plot_pos + Direction::ALL[i] + Direction::ALL[j], outside) != plot) {
+            corners += 1;
+        }
+    }
+    (perimeter, corners)
+}
+
+/// Works both on `CharMap` and on the more compact `ByteMap`.
+/// The default value of the cell, `'\0'` or `0`, marks the plots outside the garden.
+pub fn calculate_fencing_price<T: Copy + PartialEq + Default>(garden: &Grid<T>) -> (i32, i32) {
+    calculate_fencing_price_with(garden, plot_fence)
+}
+
+/// Same as `calculate_fencing_price`, but with fewer lookups of the neighbouring plots.
+pub fn calculate_fencing_price_neighbours<T: Copy + PartialEq + Default>(garden: &Grid<T>) -> (i32, i32) {
+    calculate_fencing_price_with(garden, plot_fence_neighbours)
+}
+
+fn calculate_fencing_price_with<T: Copy + PartialEq + Default, F: Fn(&Grid<T>, Point, T, T) -> (i32, i32)>(garden: &Grid<T>, fence: F) -> (i32, i32) {
+    let outside = T::default();
+    let mut garden_regions = Grid::new(garden.width(), garden.height(), 0u32);
     let mut region_fences = Vec::<Fence>::new();
     let mut region_parents = Vec::<u32>::new();
 
-    for y in 0..garden.height {
-        for x in 0..garden.width {
-            let plot = garden.get(x, y);
-            let top_pos = (x + TOP.0, y + TOP.1);
-            let top = garden.get(top_pos.0, top_pos.1);
-            let left_pos = (x + LEFT.0, y + LEFT.1);
-            let left = garden.get(left_pos.0, left_pos.1);
-
-            let mut perimeter = 0;
-            let mut corners = 0;
-            for i in 0..4 {
-                let d1 = DIRECTIONS[i];
-                let d2 = DIRECTIONS[(i + 1) % 4];
-                if garden.get(x + d1.0, y + d1.1) != plot {
-                    perimeter += 1;
-                }
-                if garden.get(x + d1.0, y + d1.1) != plot && garden.get(x + d2.0, y + d2.1) != plot {
-                    corners += 1;
-                } else if garden.get(x + d1.0, y + d1.1) == plot && garden.get(x + d2.0, y + d2.1) == plot && garden.get(x + d1.0 + d2.0, y + d1.1 + d2.1) != plot {
-                    corners += 1;
-                }
-            }
+    for y in 0..garden.height() as i32 {
+        for x in 0..garden.width() as i32 {
+            let plot_pos = Point::new(x, y);
+            let plot = garden.get_or(plot_pos, outside);
+            let top_pos = plot_pos + Direction::Up;
+            let top = garden.get_or(top_pos, outside);
+            let left_pos = plot_pos + Direction::Left;
+            let left = garden.get_or(left_pos, outside);
+
+            let (perimeter, corners) = fence(garden, plot_pos, plot, outside);
 
             if plot != top && plot != left {
                 let region = region_fences.len() as u32;
                 region_fences.push(Fence { area: 1, perimeter, corners });
                 region_parents.push(u32::max_value());
-                garden_regions.set(x as usize, y as usize, region);
+                *garden_regions.get_unchecked_mut(x as usize, y as usize) = region;
             } else if plot == top && plot == left {
-                let mut top_region = *garden_regions.get(top_pos.0 as usize, top_pos.1 as usize);
-                let mut left_region = *garden_regions.get(left_pos.0 as usize, left_pos.1 as usize);
+                let mut top_region = *garden_regions.get_unchecked(top_pos.x as usize, top_pos.y as usize);
+                let mut left_region = *garden_regions.get_unchecked(left_pos.x as usize, left_pos.y as usize);
                 while region_parents[top_region as usize] != u32::max_value() {
                     top_region = region_parents[top_region as usize];
                 }
@@ -85,26 +123,26 @@ pub fn calculate_fencing_price(garden: &CharMap) -> (i32, i32) {
                 region_fences[region as usize].area += 1;
                 region_fences[region as usize].perimeter += perimeter;
                 region_fences[region as usize].corners += corners;
-                garden_regions.set(x as usize, y as usize, region);
+                *garden_regions.get_unchecked_mut(x as usize, y as usize) = region;
             } else if plot == top {
-                let mut top_region = *garden_regions.get(top_pos.0 as usize, top_pos.1 as usize);
+                let mut top_region = *garden_regions.get_unchecked(top_pos.x as usize, top_pos.y as usize);
                 while region_parents[top_region as usize] != u32::max_value() {
                     top_region = region_parents[top_region as usize];
                 }
                 region_fences[top_region as usize].area += 1;
                 region_fences[top_region as usize].perimeter += perimeter;
                 region_fences[top_region as usize].corners += corners;
-                garden_regions.set(x as usize, y as usize, top_region);
+                *garden_regions.get_unchecked_mut(x as usize, y as usize) = top_region;
             } else {
                 //< plot == left
-                let mut left_region = *garden_regions.get(left_pos.0 as usize, left_pos.1 as usize);
+                let mut left_region = *garden_regions.get_unchecked(left_pos.x as usize, left_pos.y as usize);
                 while region_parents[left_region as usize] != u32::max_value() {
                     left_region = region_parents[left_region as usize];
                 }
                 region_fences[left_region as usize].area += 1;
                 region_fences[left_region as usize].perimeter += perimeter;
                 region_fences[left_region as usize].corners += corners;
-                garden_regions.set(x as usize, y as usize, left_region);
+                *garden_regions.get_unchecked_mut(x as usize, y as usize) = left_region;
             }
         }
     }
@@ -120,9 +158,81 @@ pub fn calculate_fencing_price(garden: &CharMap) -> (i32, i32) {
     return (price_v1, prive_v2);
 }
 
+/// Labels the plots with the index of their region, regions are numbered in the order they are found.
+pub fn garden_regions<T: Copy + PartialEq>(garden: &Grid<T>) -> Grid<u32> {
+    const UNLABELED: u32 = u32::MAX;
+    let mut regions = garden.layer(UNLABELED);
+    let mut region_count = 0;
+    for (start, &plot) in garden.cells() {
+        if regions.get(start) != Some(&UNLABELED) {
+            continue;
+        }
+        regions.set(start, region_count);
+        let mut to_visit = vec![start];
+        while let Some(point) = to_visit.pop() {
+            for next in point.neighbours() {
+                if garden.get(next) == Some(&plot) && regions.get(next) == Some(&UNLABELED) {
+                    regions.set(next, region_count);
+                    to_visit.push(next);
+                }
+            }
+        }
+        region_count += 1;
+    }
+    regions
+}
+
+#[derive(Default)]
+pub struct Day12 {
+    garden: ByteMap,
+    /// Both prices come from the same pass over the regions, which the first solved part computes.
+    prices: OnceLock<(i32, i32)>,
+}
+
+impl Day12 {
+    fn prices(&self) -> (i32, i32) {
+        *self.prices.get_or_init(|| calculate_fencing_price_neighbours(&self.garden))
+    }
+}
+
+impl Solution for Day12 {
+    fn day(&self) -> u32 {
+        12
+    }
+
+    fn parse(&mut self, input: &str) -> Result<()> {
+        self.garden = ByteMap::parse_ascii(input)?;
+        self.prices = OnceLock::new();
+        Ok(())
+    }
+
+    fn part_one(&self) -> Answer {
+        Answer::new("The fencing price is {}", self.prices().0)
+    }
+
+    fn part_two(&self) -> Answer {
+        Answer::new("The new fencing price is {}", self.prices().1)
+    }
+
+    /// Colours each region, neighbouring regions may share a colour when the palette runs out.
+    fn visualize(&self) -> Option<Canvas> {
+        let regions = garden_regions(&self.garden);
+        let renderer = Renderer::new(&self.garden).style(|point, _| {
+            let region = *regions.get_unchecked(point.x as usize, point.y as usize) as usize;
+            Style {
+                fg: Some(Color::Black),
+                ..Style::bg(Color::PALETTE[region % Color::PALETTE.len()])
+            }
+        });
+        Some(renderer.canvas())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::char_map::CharMap;
+    use crate::render::Mode;
 
     const SIMPLE_INPUT: &str = r#"AAAA
 BBCD
@@ -151,21 +261,67 @@ MMMISSJEEE
 
     #[test]
     fn test_calculate_fencing_price_simple() {
-        let garden = CharMap::new(&SIMPLE_INPUT.to_string());
+        let garden = CharMap::parse(SIMPLE_INPUT).unwrap();
         assert_eq!(calculate_fencing_price(&garden).0, 140);
     }
 
     #[test]
     fn test_calculate_fencing_price_another() {
-        let garden = CharMap::new(&ANOTHER_INPUT.to_string());
+        let garden = CharMap::parse(ANOTHER_INPUT).unwrap();
         assert_eq!(calculate_fencing_price(&garden).0, 772);
     }
 
     #[test]
     fn test_calculate_fencing_price_example() {
-        let garden = CharMap::new(&EXAMPLE_INPUT.to_string());
+        let garden = CharMap::parse(EXAMPLE_INPUT).unwrap();
         let (price_v1, price_v2) = calculate_fencing_price(&garden);
         assert_eq!(price_v1, 1930);
         assert_eq!(price_v2, 1206);
     }
+
+    #[test]
+    fn test_calculate_fencing_price_byte_map() {
+        let garden = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        assert_eq!(calculate_fencing_price(&garden), (1930, 1206));
+    }
+
+    #[test]
+    fn test_calculate_fencing_price_neighbours() {
+        let garden = ByteMap::parse_ascii(SIMPLE_INPUT).unwrap();
+        assert_eq!(calculate_fencing_price_neighbours(&garden), (140, 80));
+        let garden = ByteMap::parse_ascii(ANOTHER_INPUT).unwrap();
+        assert_eq!(calculate_fencing_price_neighbours(&garden), (772, 436));
+        let garden = ByteMap::parse_ascii(EXAMPLE_INPUT).unwrap();
+        assert_eq!(calculate_fencing_price_neighbours(&garden), (1930, 1206));
+    }
+
+    #[test]
+    fn test_garden_regions() {
+        let regions = garden_regions(&ByteMap::parse_ascii(SIMPLE_INPUT).unwrap());
+        assert_eq!(regions.row(0), &[0, 0, 0, 0]);
+        assert_eq!(regions.row(1), &[1, 1, 2, 3]);
+        assert_eq!(regions.row(3), &[4, 4, 4, 2]);
+
+        let regions = garden_regions(&ByteMap::parse_ascii(ANOTHER_INPUT).unwrap());
+        assert_eq!(regions.cells().map(|(_, &r)| r).max(), Some(4));
+    }
+
+    #[test]
+    fn test_visualize() {
+        let mut solution = Day12::default();
+        solution.parse(SIMPLE_INPUT).unwrap();
+        assert_eq!(solution.visualize().unwrap().to_string(), SIMPLE_INPUT);
+        assert!(solution.visualize().unwrap().render(Mode::Ansi).starts_with("\x1b[30;41mAAAA\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_solution_example() {
+        let mut solution = Day12::default();
+        solution.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "1930");
+        assert_eq!(solution.part_two().value, "1206");
+        // Parsing another garden drops the cached prices.
+        solution.parse(SIMPLE_INPUT).unwrap();
+        assert_eq!(solution.part_one().value, "140");
+    }
 }
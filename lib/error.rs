@@ -0,0 +1,91 @@
+﻿use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// The input is malformed. Line and column are 1-based.
+    Parse { line: usize, column: usize, message: String },
+    /// The named result does not fit in its type.
+    Overflow(&'static str),
+    /// An argument is not valid, the message tells why.
+    Invalid(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn parse<S: Into<String>>(line: usize, column: usize, message: S) -> Error {
+        Error::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Parse { line, column, message } => write!(f, "{}:{}: {}", line, column, message),
+            Error::Overflow(name) => write!(f, "{} overflows", name),
+            Error::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Parse { .. } | Error::Overflow(_) | Error::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// Returns the 1-based column of `part`, which has to be a slice of `line`.
+pub(crate) fn column_of(line: &str, part: &str) -> usize {
+    let offset = part.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
+
+/// Parses a number that is a slice of `line`, reporting its position on failure.
+pub(crate) fn parse_number<T: FromStr>(line: &str, line_number: usize, part: &str) -> Result<T> {
+    part.parse::<T>()
+        .map_err(|_| Error::parse(line_number, column_of(line, part), format!("invalid number '{}'", part)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Error::parse(14, 4, "expected ':'").to_string(), "14:4: expected ':'");
+    }
+
+    #[test]
+    fn test_column_of() {
+        let line = "12 ab 3";
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        assert_eq!(column_of(line, parts[0]), 1);
+        assert_eq!(column_of(line, parts[1]), 4);
+        assert_eq!(column_of(line, parts[2]), 7);
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let line = "12 ab";
+        assert_eq!(parse_number::<i32>(line, 1, &line[0..2]).unwrap(), 12);
+        assert_eq!(parse_number::<i32>(line, 3, &line[3..5]).unwrap_err().to_string(), "3:4: invalid number 'ab'");
+    }
+}
@@ -0,0 +1,390 @@
+﻿use crate::error::{Error, Result};
+use crate::point::Point;
+
+/// A rectangular grid of cells stored row by row.
+/// Coordinates are `x` for the column and `y` for the row. The checked accessors take a `Point`,
+/// so neighbours of the border cells can be probed without casting.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Creates the grid from cells stored row by row.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "Expected {}x{} cells", width, height);
+        Grid { cells, width, height }
+    }
+
+    /// Parses the text, one row per line, converting each character into a cell.
+    /// The `\n`, `\r\n` and `\r` line endings are accepted and the trailing empty lines are ignored.
+    /// All rows have to be as long as the first one.
+    pub fn parse_with<F: FnMut(char) -> Option<T>>(input: &str, mut convert: F) -> Result<Grid<T>> {
+        let lines = split_lines(input);
+        let height = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |last| last + 1);
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        if width == 0 && height > 0 {
+            return Err(Error::parse(1, 1, "expected cells, found an empty line"));
+        }
+
+        let mut cells = Vec::with_capacity(width * height);
+        for (line_idx, line) in lines[..height].iter().enumerate() {
+            let mut row_width = 0;
+            for c in line.chars() {
+                row_width += 1;
+                if row_width > width {
+                    break;
+                }
+                match convert(c) {
+                    Some(cell) => cells.push(cell),
+                    None => return Err(Error::parse(line_idx + 1, row_width, format!("invalid cell '{}'", c))),
+                }
+            }
+            if row_width != width {
+                let row_width = line.chars().count();
+                return Err(Error::parse(
+                    line_idx + 1,
+                    row_width.min(width) + 1,
+                    format!("expected {} cells, found {}", width, row_width),
+                ));
+            }
+        }
+
+        Ok(Grid { cells, width, height })
+    }
+
+    /// Creates a grid of the same size, e.g. a side layer with per-cell flags.
+    pub fn layer<U: Clone>(&self, value: U) -> Grid<U> {
+        Grid::new(self.width, self.height, value)
+    }
+
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.cells.fill(value);
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= 0 && (point.x as usize) < self.width && point.y >= 0 && (point.y as usize) < self.height
+    }
+
+    #[inline]
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if !self.contains(point) {
+            return None;
+        }
+        Some(&self.cells[point.y as usize * self.width + point.x as usize])
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if !self.contains(point) {
+            return None;
+        }
+        Some(&mut self.cells[point.y as usize * self.width + point.x as usize])
+    }
+
+    /// Returns the cell, or `outside` when the coordinates are out of bounds.
+    #[inline]
+    pub fn get_or(&self, point: Point, outside: T) -> T
+    where
+        T: Copy,
+    {
+        match self.get(point) {
+            Some(cell) => *cell,
+            None => outside,
+        }
+    }
+
+    /// Sets the cell. Coordinates out of bounds are ignored and `false` is returned.
+    #[inline]
+    pub fn set(&mut self, point: Point, value: T) -> bool {
+        match self.get_mut(point) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the cell without checking the row and column separately. The caller has to keep `x < width`
+    /// and `y < height`, which only debug builds assert: in release builds a column past the width reads
+    /// the next row and a cell past the end panics. It is not `unsafe`, the storage is still bounds checked.
+    #[inline]
+    pub fn get_unchecked(&self, x: usize, y: usize) -> &T {
+        debug_assert!(x < self.width && y < self.height, "Index out of bounds");
+        &self.cells[y * self.width + x]
+    }
+
+    /// Same as `get_unchecked`, with the same contract on the coordinates.
+    #[inline]
+    pub fn get_unchecked_mut(&mut self, x: usize, y: usize) -> &mut T {
+        debug_assert!(x < self.width && y < self.height, "Index out of bounds");
+        &mut self.cells[y * self.width + x]
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks_exact` panics on zero, an empty grid has no rows anyway.
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Index out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterates over the cells row by row, together with their coordinates.
+    pub fn cells(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Point::new((i % width) as i32, (i / width) as i32), cell))
+    }
+
+    /// Returns the coordinates of the first cell, row by row, matching the predicate.
+    pub fn position<P: FnMut(&T) -> bool>(&self, predicate: P) -> Option<Point> {
+        let index = self.cells.iter().position(predicate)?;
+        Some(Point::new((index % self.width) as i32, (index / self.width) as i32))
+    }
+}
+
+/// Splits the text into lines, accepting `\n`, `\r\n` and `\r` line endings.
+fn split_lines(input: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut rest = input;
+    while let Some(end) = rest.find(['\n', '\r']) {
+        lines.push(&rest[..end]);
+        let ending = if rest[end..].starts_with("\r\n") { 2 } else { 1 };
+        rest = &rest[end + ending..];
+    }
+    if !rest.is_empty() {
+        lines.push(rest);
+    }
+    lines
+}
+
+impl Grid<char> {
+    /// Parses the text into a grid of its characters.
+    pub fn parse(input: &str) -> Result<Grid<char>> {
+        Grid::parse_with(input, Some)
+    }
+}
+
+impl Grid<u8> {
+    /// Parses the text into a grid of its bytes, 1 byte per cell. Only ASCII text is accepted.
+    pub fn parse_ascii(input: &str) -> Result<Grid<u8>> {
+        Grid::parse_with(input, |c| c.is_ascii().then_some(c as u8))
+    }
+}
+
+impl<T: Clone> Clone for Grid<T> {
+    fn clone(&self) -> Self {
+        Grid {
+            cells: self.cells.clone(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Reuses the allocated cells, which makes copying a grid in a loop cheap.
+    fn clone_from(&mut self, source: &Self) {
+        self.cells.clone_from(&source.cells);
+        self.width = source.width;
+        self.height = source.height;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "abc\ndef\n";
+
+    #[test]
+    fn test_grid_new() {
+        let grid = Grid::new(10, 5, 1);
+        assert_eq!(grid.width(), 10);
+        assert_eq!(grid.height(), 5);
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&1));
+        assert_eq!(grid.get(Point::new(9, 4)), Some(&1));
+    }
+
+    #[test]
+    fn test_grid_parse() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&'a'));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'f'));
+
+        assert_eq!(Grid::parse("abc\r\ndef").unwrap(), grid);
+        assert_eq!(Grid::parse("abc\rdef\r").unwrap(), grid);
+        assert_eq!(Grid::parse("abc\ndef\n\n\n").unwrap(), grid);
+        assert_eq!(Grid::parse("").unwrap().height(), 0);
+        assert_eq!(Grid::parse("žluť\nkůň!").unwrap().width(), 4);
+    }
+
+    #[test]
+    fn test_split_lines() {
+        assert_eq!(split_lines("a\nb\r\nc\rd"), vec!["a", "b", "c", "d"]);
+        assert_eq!(split_lines("a\n\nb\n"), vec!["a", "", "b"]);
+        assert_eq!(split_lines("a\r\n\r\n"), vec!["a", ""]);
+        assert!(split_lines("").is_empty());
+    }
+
+    #[test]
+    fn test_grid_parse_with() {
+        let grid = Grid::parse_with("123\n456\n", |c| c.to_digit(10)).unwrap();
+        assert_eq!(grid.get(Point::new(1, 1)), Some(&5));
+
+        let error = Grid::parse_with("123\n4x6\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.to_string(), "2:2: invalid cell 'x'");
+    }
+
+    #[test]
+    fn test_grid_parse_ascii() {
+        let grid = Grid::parse_ascii(INPUT).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.row(1), b"def");
+
+        let error = Grid::parse_ascii("abc\ndéf\n").unwrap_err();
+        assert_eq!(error.to_string(), "2:2: invalid cell 'é'");
+    }
+
+    #[test]
+    fn test_grid_layer() {
+        let grid = Grid::parse_ascii(INPUT).unwrap();
+        let mut flags = grid.layer(0u8);
+        assert_eq!(flags.width(), grid.width());
+        assert_eq!(flags.height(), grid.height());
+        assert!(flags.cells().all(|(_, &f)| f == 0));
+
+        flags.set(Point::new(1, 1), 4);
+        assert_eq!(flags.get(Point::new(1, 1)), Some(&4));
+        flags.fill(1);
+        assert!(flags.cells().all(|(_, &f)| f == 1));
+    }
+
+    #[test]
+    fn test_grid_parse_ragged() {
+        let error = Grid::parse("abc\nde\nfgh\n").unwrap_err();
+        assert_eq!(error.to_string(), "2:3: expected 3 cells, found 2");
+
+        let error = Grid::parse("abc\ndefg\n").unwrap_err();
+        assert_eq!(error.to_string(), "2:4: expected 3 cells, found 4");
+
+        let error = Grid::parse("abc\n\ndef\n").unwrap_err();
+        assert_eq!(error.to_string(), "2:1: expected 3 cells, found 0");
+
+        let error = Grid::parse("\nabc\ndef\n").unwrap_err();
+        assert_eq!(error.to_string(), "1:1: expected cells, found an empty line");
+    }
+
+    #[test]
+    fn test_grid_get_outside_bounds() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 2)), None);
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -10)), None);
+
+        assert_eq!(grid.get_or(Point::new(1, 1), '\0'), 'e');
+        assert_eq!(grid.get_or(Point::new(-1, 1), '\0'), '\0');
+        assert_eq!(grid.get_or(Point::new(1, 5), '#'), '#');
+    }
+
+    #[test]
+    fn test_grid_set() {
+        let mut grid = Grid::parse(INPUT).unwrap();
+        assert!(grid.set(Point::new(1, 0), 'X'));
+        assert_eq!(grid.get(Point::new(1, 0)), Some(&'X'));
+        assert!(!grid.set(Point::new(3, 0), 'X'));
+        assert!(!grid.set(Point::new(0, -1), 'X'));
+
+        *grid.get_mut(Point::new(2, 1)).unwrap() = 'Y';
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'Y'));
+        assert!(grid.get_mut(Point::new(2, 2)).is_none());
+    }
+
+    #[test]
+    fn test_grid_get_unchecked() {
+        let mut grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.get_unchecked(2, 0), &'c');
+        *grid.get_unchecked_mut(0, 1) = 'Z';
+        assert_eq!(grid.get_unchecked(0, 1), &'Z');
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Index out of bounds")]
+    fn test_grid_get_unchecked_past_row_end() {
+        // Inside the storage, but past the end of the first row.
+        Grid::parse(INPUT).unwrap().get_unchecked(3, 0);
+    }
+
+    #[test]
+    fn test_grid_rows_columns() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&['a', 'b', 'c'], &['d', 'e', 'f']]);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().map(|c| c.collect::<String>()).collect::<Vec<_>>(), vec!["ad", "be", "cf"]);
+
+        let empty = Grid::<char>::default();
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.columns().count(), 0);
+    }
+
+    #[test]
+    fn test_grid_cells_position() {
+        let grid = Grid::parse(INPUT).unwrap();
+        let cells: Vec<(Point, char)> = grid.cells().map(|(pos, c)| (pos, *c)).collect();
+        assert_eq!(cells[0], (Point::new(0, 0), 'a'));
+        assert_eq!(cells[4], (Point::new(1, 1), 'e'));
+
+        assert_eq!(grid.position(|&c| c == 'e'), Some(Point::new(1, 1)));
+        assert_eq!(grid.position(|&c| c == 'x'), None);
+    }
+
+    #[test]
+    fn test_grid_clone_from() {
+        let grid = Grid::parse(INPUT).unwrap();
+        let mut copy = Grid::new(1, 1, '.');
+        copy.clone_from(&grid);
+        assert_eq!(copy, grid);
+    }
+}
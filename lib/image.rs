@@ -0,0 +1,398 @@
+﻿use crate::grid::Grid;
+use crate::point::Point;
+use crate::render::{Canvas, Cell, Color};
+use std::io::{self, Write};
+use std::str::FromStr;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Rgb {
+        Rgb { r, g, b }
+    }
+}
+
+/// Colours standing for the terminal colours when a canvas is exported as an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// Indexed by `Color`.
+    pub colors: [Rgb; 16],
+    /// Colour of the cells without a style.
+    pub background: Rgb,
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        colors: [
+            Rgb::new(0, 0, 0),
+            Rgb::new(205, 49, 49),
+            Rgb::new(13, 188, 121),
+            Rgb::new(229, 229, 16),
+            Rgb::new(36, 114, 200),
+            Rgb::new(188, 63, 188),
+            Rgb::new(17, 168, 205),
+            Rgb::new(229, 229, 229),
+            Rgb::new(102, 102, 102),
+            Rgb::new(241, 76, 76),
+            Rgb::new(35, 209, 139),
+            Rgb::new(245, 245, 67),
+            Rgb::new(59, 142, 234),
+            Rgb::new(214, 112, 214),
+            Rgb::new(41, 184, 219),
+            Rgb::new(255, 255, 255),
+        ],
+        background: Rgb::new(30, 30, 30),
+    };
+
+    pub const LIGHT: Palette = Palette {
+        colors: [
+            Rgb::new(0, 0, 0),
+            Rgb::new(205, 49, 49),
+            Rgb::new(0, 188, 0),
+            Rgb::new(148, 152, 0),
+            Rgb::new(4, 81, 165),
+            Rgb::new(188, 5, 188),
+            Rgb::new(5, 152, 188),
+            Rgb::new(85, 85, 85),
+            Rgb::new(170, 170, 170),
+            Rgb::new(205, 49, 49),
+            Rgb::new(20, 206, 20),
+            Rgb::new(181, 186, 0),
+            Rgb::new(4, 81, 165),
+            Rgb::new(188, 5, 188),
+            Rgb::new(5, 152, 188),
+            Rgb::new(165, 165, 165),
+        ],
+        background: Rgb::new(255, 255, 255),
+    };
+
+    pub fn color(&self, color: Color) -> Rgb {
+        self.colors[color as usize]
+    }
+
+    /// Returns the colour of the cell: its background, else its foreground, else the palette background.
+    pub fn cell(&self, cell: &Cell) -> Rgb {
+        cell.style.bg.or(cell.style.fg).map_or(self.background, |color| self.color(color))
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::DARK
+    }
+}
+
+impl FromStr for Palette {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Palette, ()> {
+        match s {
+            "dark" => Ok(Palette::DARK),
+            "light" => Ok(Palette::LIGHT),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    /// Binary portable pixmap (P6).
+    Ppm,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Ppm => "ppm",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<ImageFormat, ()> {
+        match s {
+            "png" => Ok(ImageFormat::Png),
+            "ppm" => Ok(ImageFormat::Ppm),
+            _ => Err(()),
+        }
+    }
+}
+
+/// An image is a grid of pixels.
+pub type Image = Grid<Rgb>;
+
+impl Image {
+    /// Draws each cell of the grid as a square of `cell_size` × `cell_size` pixels of the colour returned by the function.
+    pub fn from_grid<T, F: Fn(Point, &T) -> Rgb>(grid: &Grid<T>, cell_size: usize, color: F) -> Image {
+        let mut image = Image::new(grid.width() * cell_size, grid.height() * cell_size, Rgb::default());
+        for (point, cell) in grid.cells() {
+            let rgb = color(point, cell);
+            let (x, y) = (point.x as usize * cell_size, point.y as usize * cell_size);
+            for dy in 0..cell_size {
+                for dx in 0..cell_size {
+                    *image.get_unchecked_mut(x + dx, y + dy) = rgb;
+                }
+            }
+        }
+        image
+    }
+
+    /// Draws the canvas with the palette, see `Palette::cell`. The characters are not drawn.
+    pub fn from_canvas(canvas: &Canvas, cell_size: usize, palette: &Palette) -> Image {
+        Image::from_grid(canvas, cell_size, |_, cell| palette.cell(cell))
+    }
+
+    pub fn write<W: Write>(&self, format: ImageFormat, out: W) -> io::Result<()> {
+        match format {
+            ImageFormat::Png => self.write_png(out),
+            ImageFormat::Ppm => self.write_ppm(out),
+        }
+    }
+
+    pub fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width(), self.height())?;
+        let bytes: Vec<u8> = self.rows().flat_map(|row| row.iter().flat_map(|p| [p.r, p.g, p.b])).collect();
+        out.write_all(&bytes)
+    }
+
+    /// Writes an 8-bit RGB PNG. Puzzle pictures consist of large areas of a single colour,
+    /// so the rows are filtered to runs of zeros which are compressed as repeated bytes.
+    pub fn write_png<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width() as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height() as u32).to_be_bytes());
+        // Bit depth 8, colour type 2 (RGB), default compression, filtering and no interlace.
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_chunk(&mut out, b"IHDR", &header)?;
+        write_chunk(&mut out, b"IDAT", &zlib(&self.filtered_rows()))?;
+        write_chunk(&mut out, b"IEND", &[])
+    }
+
+    /// Returns the rows prefixed by their filter type. A row repeating the previous one uses the Up filter,
+    /// the others use the Sub filter, both leave zeros for repeated pixels.
+    fn filtered_rows(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity((self.width() * 3 + 1) * self.height());
+        let mut previous: &[Rgb] = &[];
+        for row in self.rows() {
+            if row == previous {
+                data.push(2);
+                data.resize(data.len() + row.len() * 3, 0);
+            } else {
+                data.push(1);
+                let mut left = Rgb::default();
+                for &pixel in row {
+                    data.extend_from_slice(&[pixel.r.wrapping_sub(left.r), pixel.g.wrapping_sub(left.g), pixel.b.wrapping_sub(left.b)]);
+                    left = pixel;
+                }
+            }
+            previous = row;
+        }
+        data
+    }
+}
+
+fn write_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+fn crc32<'a, I: IntoIterator<Item = &'a u8>>(bytes: I) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// Writes the bits of a deflate stream, least significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are stored starting from their most significant bit.
+    fn code(&mut self, code: u32, length: u32) {
+        self.bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    /// Writes a literal, length or end-of-block symbol with the fixed Huffman codes.
+    fn symbol(&mut self, symbol: u32) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+const LENGTH_BASE: [u32; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u32; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const MAX_MATCH: usize = 258;
+
+/// Compresses the data into a single fixed Huffman block. The only matches are runs of the previous byte.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    // Final block, fixed Huffman codes.
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+
+    let mut i = 0;
+    while i < data.len() {
+        let run = if i == 0 {
+            0
+        } else {
+            data[i..].iter().take(MAX_MATCH).take_while(|&&byte| byte == data[i - 1]).count()
+        };
+        if run >= 3 {
+            let index = LENGTH_BASE.iter().rposition(|&base| base <= run as u32).unwrap();
+            writer.symbol(257 + index as u32);
+            writer.bits(run as u32 - LENGTH_BASE[index], LENGTH_EXTRA_BITS[index]);
+            // Distance code 0 is a distance of 1.
+            writer.code(0, 5);
+            i += run;
+        } else {
+            writer.symbol(data[i] as u32);
+            i += 1;
+        }
+    }
+    writer.symbol(256);
+    writer.finish()
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, no preset dictionary, fastest compression level.
+    let mut stream = vec![0x78, 0x01];
+    stream.extend(deflate(data));
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{Renderer, Style};
+
+    fn image() -> Image {
+        let grid = Grid::parse("#.\n.#\n").unwrap();
+        Image::from_grid(&grid, 2, |_, &c| if c == '#' { Rgb::new(255, 0, 0) } else { Rgb::new(0, 0, 255) })
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let image = image();
+        assert_eq!((image.width(), image.height()), (4, 4));
+        let red = Rgb::new(255, 0, 0);
+        let blue = Rgb::new(0, 0, 255);
+        assert_eq!(image.row(0), &[red, red, blue, blue]);
+        assert_eq!(image.row(1), &[red, red, blue, blue]);
+        assert_eq!(image.row(3), &[blue, blue, red, red]);
+    }
+
+    #[test]
+    fn test_from_canvas() {
+        let grid = Grid::parse("ab.\n").unwrap();
+        let canvas = Renderer::new(&grid)
+            .style(|_, &c| match c {
+                'a' => Style::fg(Color::Red),
+                'b' => Style {
+                    fg: Some(Color::Red),
+                    ..Style::bg(Color::Blue)
+                },
+                _ => Style::NONE,
+            })
+            .canvas();
+        let palette = Palette::LIGHT;
+        let image = Image::from_canvas(&canvas, 1, &palette);
+        assert_eq!(image.row(0), &[palette.color(Color::Red), palette.color(Color::Blue), palette.background]);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut out = Vec::new();
+        Image::from_cells(2, 1, vec![Rgb::new(1, 2, 3), Rgb::new(4, 5, 6)]).write_ppm(&mut out).unwrap();
+        assert_eq!(out, b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+    }
+
+    #[test]
+    fn test_write_png() {
+        let mut out = Vec::new();
+        image().write(ImageFormat::Png, &mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x04\x00\x00\x00\x04\x08\x02\x00\x00\x00"));
+        assert!(out.ends_with(b"\x00\x00\x00\x00IEND\xae\x42\x60\x82"));
+    }
+
+    #[test]
+    fn test_filtered_rows() {
+        let data = image().filtered_rows();
+        assert_eq!(data.len(), 4 * 13);
+        assert_eq!(&data[..13], &[1, 255, 0, 0, 0, 0, 0, 1, 0, 255, 0, 0, 0]);
+        assert_eq!(data[13], 2);
+        assert!(data[14..26].iter().all(|&byte| byte == 0));
+        assert_eq!(data[26], 1);
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_deflate() {
+        // Literals only, checked against zlib.
+        assert_eq!(deflate(b"ab"), [0x4b, 0x4c, 0x02, 0x00]);
+        assert_eq!(deflate(b""), [0x03, 0x00]);
+        // A long run is compressed to a few bytes.
+        assert!(deflate(&[0; 10_000]).len() < 100);
+    }
+}
@@ -0,0 +1,54 @@
+﻿use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the directory with the puzzle inputs.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// Returns the directory with the puzzle inputs.
+/// Uses `AOC_INPUT_DIR` when it is set, otherwise the `inputs` directory in the crate root.
+pub fn input_dir() -> PathBuf {
+    match env::var_os(INPUT_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"),
+    }
+}
+
+/// Returns the input file name for the given day, e.g. `day_07.txt`.
+pub fn input_file_name(day: u32) -> String {
+    format!("day_{:02}.txt", day)
+}
+
+/// Returns the path to the input file of the given day.
+pub fn input_path(day: u32) -> PathBuf {
+    input_dir().join(input_file_name(day))
+}
+
+/// Returns the path to the file with the known answers for the inputs.
+pub fn answers_path() -> PathBuf {
+    input_dir().join("answers.txt")
+}
+
+/// Reads the whole input of the given day.
+pub fn read_input(day: u32) -> io::Result<String> {
+    fs::read_to_string(input_path(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_file_name() {
+        assert_eq!(input_file_name(1), "day_01.txt");
+        assert_eq!(input_file_name(12), "day_12.txt");
+    }
+
+    #[test]
+    fn test_input_path() {
+        let path = input_path(7);
+        assert_eq!(path.file_name().unwrap(), "day_07.txt");
+        assert_eq!(path.parent().unwrap(), input_dir());
+    }
+}
@@ -1,4 +1,5 @@
-﻿pub mod char_map;
+﻿pub mod answers;
+pub mod char_map;
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
@@ -11,4 +12,12 @@ pub mod day_09;
 pub mod day_10;
 pub mod day_11;
 pub mod day_12;
-pub mod matrix;
+pub mod error;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod point;
+pub mod render;
+pub mod solution;
+
+pub use error::Error;
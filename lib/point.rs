@@ -0,0 +1,213 @@
+﻿use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position on a grid, or an offset between two positions. `y` grows downwards.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Offsets to the 8 surrounding positions, clockwise from up.
+const NEIGHBOURS_8: [Point; 8] = [
+    Point::new(0, -1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
+    Point::new(0, 1),
+    Point::new(-1, 1),
+    Point::new(-1, 0),
+    Point::new(-1, -1),
+];
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    pub fn manhattan_distance(self, other: Point) -> u32 {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+
+    /// Returns the 4 orthogonally adjacent positions, clockwise from up.
+    pub fn neighbours(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |direction| self + direction)
+    }
+
+    /// Returns the 8 adjacent positions including the diagonal ones, clockwise from up.
+    pub fn neighbours_8(self) -> impl Iterator<Item = Point> {
+        NEIGHBOURS_8.into_iter().map(move |offset| self + offset)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Point {
+        Point { x, y }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, direction: Direction) {
+        *self = *self + direction;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Sub<Direction> for Point {
+    type Output = Point;
+
+    fn sub(self, direction: Direction) -> Point {
+        self - direction.offset()
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, scale: i32) -> Point {
+        Point::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// One of the 4 directions on a grid, ordered clockwise from up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    #[default]
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub const fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    /// Returns the index of the direction in `Direction::ALL`, handy for bit masks and lookup tables.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn turn_right(self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub const fn turn_left(self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    pub const fn opposite(self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point::new(3, -2);
+        let b = Point::new(1, 5);
+        assert_eq!(a + b, Point::new(4, 3));
+        assert_eq!(a - b, Point::new(2, -7));
+        assert_eq!(a * 3, Point::new(9, -6));
+        assert_eq!(-a, Point::new(-3, 2));
+        assert_eq!(a + Direction::Up, Point::new(3, -3));
+        assert_eq!(a - Direction::Right, Point::new(2, -2));
+
+        let mut c = a;
+        c += b;
+        c -= Point::new(1, 1);
+        c += Direction::Down;
+        assert_eq!(c, Point::new(3, 3));
+        assert_eq!(Point::from((7, 8)), Point::new(7, 8));
+    }
+
+    #[test]
+    fn test_manhattan_distance() {
+        assert_eq!(Point::new(0, 0).manhattan_distance(Point::new(0, 0)), 0);
+        assert_eq!(Point::new(1, -2).manhattan_distance(Point::new(-3, 4)), 10);
+        assert_eq!(Point::new(-3, 4).manhattan_distance(Point::new(1, -2)), 10);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point::new(5, 5);
+        let neighbours: Vec<Point> = p.neighbours().collect();
+        assert_eq!(neighbours, vec![Point::new(5, 4), Point::new(6, 5), Point::new(5, 6), Point::new(4, 5)]);
+
+        let neighbours: Vec<Point> = p.neighbours_8().collect();
+        assert_eq!(neighbours.len(), 8);
+        assert!(neighbours.iter().all(|n| *n != p && (n.x - p.x).abs() <= 1 && (n.y - p.y).abs() <= 1));
+        assert_eq!(neighbours[1], Point::new(6, 4));
+    }
+
+    #[test]
+    fn test_direction_turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Down.turn_left(), Direction::Right);
+        assert_eq!(Direction::Right.opposite(), Direction::Left);
+
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.offset() + direction.opposite().offset(), Point::default());
+            assert_eq!(Direction::ALL[direction.index()], direction);
+            // Turning right rotates the offset clockwise, (x, y) becomes (-y, x).
+            let offset = direction.offset();
+            assert_eq!(direction.turn_right().offset(), Point::new(-offset.y, offset.x));
+        }
+    }
+}
@@ -0,0 +1,318 @@
+﻿use crate::grid::Grid;
+use crate::point::Point;
+use std::collections::HashSet;
+use std::fmt;
+
+/// The 16 standard terminal colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl Color {
+    /// Distinct background-friendly colours, e.g. for telling regions apart.
+    pub const PALETTE: [Color; 12] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+    ];
+
+    fn ansi_code(self, background: bool) -> u8 {
+        let index = self as u8;
+        let base = if background { 40 } else { 30 };
+        if index < 8 {
+            base + index
+        } else {
+            base + 60 + index - 8
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub const NONE: Style = Style { fg: None, bg: None, bold: false };
+
+    pub const fn fg(color: Color) -> Style {
+        Style {
+            fg: Some(color),
+            bg: None,
+            bold: false,
+        }
+    }
+
+    pub const fn bg(color: Color) -> Style {
+        Style {
+            fg: None,
+            bg: Some(color),
+            bold: false,
+        }
+    }
+
+    pub const fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    /// Returns the escape sequence switching the terminal to this style.
+    fn ansi(&self) -> String {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push(1);
+        }
+        if let Some(fg) = self.fg {
+            codes.push(fg.ansi_code(false));
+        }
+        if let Some(bg) = self.bg {
+            codes.push(bg.ansi_code(true));
+        }
+        let codes: Vec<String> = codes.iter().map(|c| c.to_string()).collect();
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// How the grid is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Just the characters, the styles are ignored.
+    Plain,
+    /// Characters with ANSI escape sequences for the styles.
+    Ansi,
+}
+
+/// Options of the frames rendered by `Solution::animate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationOptions {
+    /// Render only every n-th step, the last one is always rendered.
+    pub step: usize,
+    /// Append frames showing the candidate solutions found along the way, like the obstructions looping the guard on day 6.
+    pub candidates: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> AnimationOptions {
+        AnimationOptions { step: 1, candidates: false }
+    }
+}
+
+/// Character representing a cell when the grid is rendered.
+pub trait Glyph {
+    fn glyph(&self) -> char;
+}
+
+impl Glyph for char {
+    fn glyph(&self) -> char {
+        *self
+    }
+}
+
+impl Glyph for u8 {
+    fn glyph(&self) -> char {
+        *self as char
+    }
+}
+
+impl Glyph for bool {
+    fn glyph(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+struct Overlay {
+    points: HashSet<Point>,
+    glyph: Option<char>,
+    style: Style,
+}
+
+type StyleFn<'a, T> = Box<dyn Fn(Point, &T) -> Style + 'a>;
+
+/// Renders a grid as text, optionally with per-cell styles and overlaid sets of points.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    style: Option<StyleFn<'a, T>>,
+    overlays: Vec<Overlay>,
+}
+
+impl<'a, T: Glyph> Renderer<'a, T> {
+    pub fn new(grid: &'a Grid<T>) -> Renderer<'a, T> {
+        Renderer {
+            grid,
+            style: None,
+            overlays: Vec::new(),
+        }
+    }
+
+    /// Sets the function styling the cells not covered by an overlay.
+    pub fn style<F: Fn(Point, &T) -> Style + 'a>(mut self, style: F) -> Renderer<'a, T> {
+        self.style = Some(Box::new(style));
+        self
+    }
+
+    /// Draws the points with the style, replacing their characters by `glyph` when given.
+    /// Overlays added later are drawn on top of the earlier ones.
+    pub fn overlay<I: IntoIterator<Item = Point>>(mut self, points: I, glyph: Option<char>, style: Style) -> Renderer<'a, T> {
+        self.overlays.push(Overlay {
+            points: points.into_iter().collect(),
+            glyph,
+            style,
+        });
+        self
+    }
+
+    fn cell(&self, point: Point, cell: &T) -> Cell {
+        let mut glyph = cell.glyph();
+        let mut style = self.style.as_ref().map_or(Style::NONE, |style| style(point, cell));
+        for overlay in self.overlays.iter().filter(|overlay| overlay.points.contains(&point)) {
+            glyph = overlay.glyph.unwrap_or(glyph);
+            style = overlay.style;
+        }
+        Cell { glyph, style }
+    }
+
+    /// Resolves the styles and overlays of all cells, the result can be rendered as text or as an image.
+    pub fn canvas(&self) -> Canvas {
+        let cells = self.grid.cells().map(|(point, cell)| self.cell(point, cell)).collect();
+        Grid::from_cells(self.grid.width(), self.grid.height(), cells)
+    }
+
+    pub fn render(&self, mode: Mode) -> String {
+        self.canvas().render(mode)
+    }
+}
+
+/// A rendered cell, the character with its style.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub style: Style,
+}
+
+impl Glyph for Cell {
+    fn glyph(&self) -> char {
+        self.glyph
+    }
+}
+
+/// A grid whose styles and overlays are already resolved.
+pub type Canvas = Grid<Cell>;
+
+impl Canvas {
+    pub fn render(&self, mode: Mode) -> String {
+        let mut text = String::with_capacity((self.width() + 1) * self.height());
+        let mut current = Style::NONE;
+        for (point, cell) in self.cells() {
+            if mode == Mode::Ansi && cell.style != current {
+                if current != Style::NONE {
+                    text.push_str(RESET);
+                }
+                if cell.style != Style::NONE {
+                    text.push_str(&cell.style.ansi());
+                }
+                current = cell.style;
+            }
+            text.push(cell.glyph);
+
+            if point.x as usize + 1 == self.width() {
+                if current != Style::NONE {
+                    text.push_str(RESET);
+                    current = Style::NONE;
+                }
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+impl<T: Glyph> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Renderer::new(self).render(Mode::Plain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "..#\n.^.\n...\n";
+
+    #[test]
+    fn test_render_plain() {
+        let grid = Grid::parse(INPUT).unwrap();
+        assert_eq!(Renderer::new(&grid).render(Mode::Plain), INPUT);
+        assert_eq!(grid.to_string(), INPUT);
+
+        let bytes = Grid::parse_ascii(INPUT).unwrap();
+        assert_eq!(bytes.to_string(), INPUT);
+
+        let flags = Grid::from_cells(2, 1, vec![true, false]);
+        assert_eq!(flags.to_string(), "#.\n");
+    }
+
+    #[test]
+    fn test_render_overlay() {
+        let grid = Grid::parse(INPUT).unwrap();
+        let renderer = Renderer::new(&grid)
+            .overlay([Point::new(1, 0), Point::new(1, 2)], Some('X'), Style::NONE)
+            .overlay([Point::new(1, 2)], Some('O'), Style::NONE)
+            .overlay([Point::new(0, 0)], None, Style::fg(Color::Red));
+        assert_eq!(renderer.render(Mode::Plain), ".X#\n.^.\n.O.\n");
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let grid = Grid::parse("ab\ncd\n").unwrap();
+        let renderer = Renderer::new(&grid)
+            .style(|_, &c| if c == 'a' || c == 'b' { Style::fg(Color::Red) } else { Style::NONE })
+            .overlay([Point::new(1, 1)], None, Style::bg(Color::BrightBlue).bold());
+        assert_eq!(renderer.render(Mode::Ansi), "\x1b[31mab\x1b[0m\nc\x1b[1;104md\x1b[0m\n");
+
+        assert_eq!(Renderer::new(&grid).render(Mode::Ansi), "ab\ncd\n");
+
+        let canvas = renderer.canvas();
+        assert_eq!(canvas.get(Point::new(1, 1)).unwrap().style, Style::bg(Color::BrightBlue).bold());
+        assert_eq!(canvas.to_string(), "ab\ncd\n");
+    }
+
+    #[test]
+    fn test_color_codes() {
+        assert_eq!(Color::Black.ansi_code(false), 30);
+        assert_eq!(Color::White.ansi_code(true), 47);
+        assert_eq!(Color::BrightBlack.ansi_code(false), 90);
+        assert_eq!(Color::BrightWhite.ansi_code(true), 107);
+    }
+}
@@ -0,0 +1,91 @@
+﻿use crate::error::Result;
+use crate::render::{AnimationOptions, Canvas};
+use crate::{day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_10, day_11, day_12};
+
+/// Result of a single puzzle part.
+pub struct Answer {
+    /// Sentence describing the answer. The `{}` placeholder marks where the value goes.
+    pub description: &'static str,
+    pub value: String,
+}
+
+impl Answer {
+    pub fn new<T: ToString>(description: &'static str, value: T) -> Answer {
+        Answer {
+            description,
+            value: value.to_string(),
+        }
+    }
+
+    /// Returns the description with the placeholder replaced by the given (possibly decorated) value.
+    pub fn describe(&self, value: &str) -> String {
+        self.description.replacen("{}", value, 1)
+    }
+}
+
+/// A single day of the puzzle. The input is parsed once and then both parts are solved on it.
+/// Days are solved on worker threads and both parts may run at the same time, hence `Send + Sync`.
+pub trait Solution: Send + Sync {
+    fn day(&self) -> u32;
+    fn parse(&mut self, input: &str) -> Result<()>;
+    fn part_one(&self) -> Answer;
+    fn part_two(&self) -> Answer;
+
+    /// Renders the parsed input annotated with the solution, if the day supports it.
+    fn visualize(&self) -> Option<Canvas> {
+        None
+    }
+
+    /// Renders the steps towards the solution as animation frames, if the day supports it.
+    fn animate(&self, _options: AnimationOptions) -> Option<Box<dyn Iterator<Item = Canvas> + '_>> {
+        None
+    }
+}
+
+/// Returns all implemented days, sorted by the day number.
+pub fn solutions() -> Vec<Box<dyn Solution>> {
+    vec![
+        Box::new(day_01::Day01::default()),
+        Box::new(day_02::Day02::default()),
+        Box::new(day_03::Day03::default()),
+        Box::new(day_04::Day04::default()),
+        Box::new(day_05::Day05::default()),
+        Box::new(day_06::Day06::default()),
+        Box::new(day_07::Day07::default()),
+        Box::new(day_08::Day08::default()),
+        Box::new(day_09::Day09::default()),
+        Box::new(day_10::Day10::default()),
+        Box::new(day_11::Day11::default()),
+        Box::new(day_12::Day12::default()),
+    ]
+}
+
+/// Returns the solution for the given day, if it is implemented.
+pub fn find_solution(day: u32) -> Option<Box<dyn Solution>> {
+    solutions().into_iter().find(|s| s.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solutions_sorted() {
+        let days: Vec<u32> = solutions().iter().map(|s| s.day()).collect();
+        assert_eq!(days, (1..=12).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_find_solution() {
+        assert_eq!(find_solution(7).map(|s| s.day()), Some(7));
+        assert!(find_solution(0).is_none());
+        assert!(find_solution(26).is_none());
+    }
+
+    #[test]
+    fn test_answer_describe() {
+        let answer = Answer::new("The word XMAS appears {} times", 18);
+        assert_eq!(answer.value, "18");
+        assert_eq!(answer.describe("**18**"), "The word XMAS appears **18** times");
+    }
+}
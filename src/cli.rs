@@ -0,0 +1,427 @@
+﻿use crate::report::Format;
+use advent::image::{ImageFormat, Palette};
+use advent::render::AnimationOptions;
+use std::path::PathBuf;
+
+pub const USAGE: &str = r#"Usage: AdventOfCode2024 [COMMAND] [OPTIONS]
+
+Commands:
+  run                   Run the selected days (default)
+  verify                Check the selected days against the known answers
+  readme                Update the results section of README.md with all days
+  animate               Save the steps of solving one day as numbered images
+                        'day_DD_NNNNN.png' in the '--image' directory
+  help                  Print this help
+
+Options:
+  -a, --all             Run all days (default)
+  -d, --day <DAY>       Run only the given day, can be repeated
+  -p, --part <PART>     Run only the given part, 1 or 2
+  -i, --input <FILE>    Read the input from the file, '-' reads from stdin
+                        Requires exactly one day
+      --answers <FILE>  Read the known answers from the file (verify only)
+  -f, --format <FORMAT> Output format of the results: markdown (default), json
+                        or csv (run only)
+  -o, --output <FILE>   Update the given README file instead (readme only)
+  -v, --visualize       Print the input map annotated with the solution, for the
+                        days supporting it, coloured on terminals unless NO_COLOR
+                        is set (run only, markdown format)
+      --image <DIR>     Save the annotated map of the days supporting it as
+                        'day_DD.png' images in the directory (run and animate)
+      --image-format <FORMAT>
+                        Format of the images: png (default) or ppm
+      --cell-size <N>   Size of a map cell in pixels (default 4)
+      --palette <NAME>  Colours of the images: dark (default) or light
+      --frame-step <N>  Save only every N-th step, the last one is always saved
+                        (animate only, default 1)
+      --candidates      Append a frame for each candidate solution, like the
+                        obstructions looping the guard on day 6 (animate only)
+  -t, --time            Print a table with the parse and part times, for readme
+                        add a column with the part times
+  -r, --repeat <N>      Repeat parsing and each part N times and report the median
+                        time, implies '--time'
+  -j, --jobs <N>        Solve up to N days at once, both parts of a day run in
+                        parallel too when N > 1, 0 uses all cores (default 1)
+
+By default the inputs are read from the 'inputs' directory in the crate root,
+the AOC_INPUT_DIR environment variable overrides it. The known answers are read
+from 'answers.txt' in the same directory.
+"#;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run,
+    Verify,
+    Readme,
+    Animate,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    File(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    /// Days to run. Empty means all days.
+    pub days: Vec<u32>,
+    /// Part to run. `None` means both parts.
+    pub part: Option<u32>,
+    pub input: Option<Input>,
+    /// File with the known answers. `None` means the default one.
+    pub answers: Option<PathBuf>,
+    /// Output format of the results.
+    pub format: Format,
+    /// README file to update. `None` means the one in the crate root.
+    pub output: Option<PathBuf>,
+    /// Print the annotated map of each day.
+    pub visualize: bool,
+    /// Directory where the annotated maps are saved as images.
+    pub image: Option<PathBuf>,
+    pub image_format: ImageFormat,
+    /// Size of a map cell in pixels.
+    pub cell_size: usize,
+    pub palette: Palette,
+    pub animation: AnimationOptions,
+    /// Print the timing summary.
+    pub time: bool,
+    /// How many times parsing and each part are repeated when measuring the time.
+    pub repeat: u32,
+    /// How many days are solved at once. Zero means one per available core.
+    pub jobs: usize,
+}
+
+fn parse_value<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or(format!("Missing value for '{}'", option))?;
+    value.parse::<T>().map_err(|_| format!("Invalid value '{}' for '{}'", value, option))
+}
+
+/// Parses the command line arguments, without the program name.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options {
+        command: Command::Run,
+        days: Vec::new(),
+        part: None,
+        input: None,
+        answers: None,
+        format: Format::Markdown,
+        output: None,
+        visualize: false,
+        image: None,
+        image_format: ImageFormat::Png,
+        cell_size: 4,
+        palette: Palette::DARK,
+        animation: AnimationOptions::default(),
+        time: false,
+        repeat: 1,
+        jobs: 1,
+    };
+    let mut all = false;
+    let mut image_options = false;
+    let mut animation_options = false;
+
+    let mut args = args.into_iter().peekable();
+    match args.peek().map(|s| s.as_str()) {
+        Some("run") => {
+            args.next();
+        }
+        Some("verify") => {
+            options.command = Command::Verify;
+            args.next();
+        }
+        Some("readme") => {
+            options.command = Command::Readme;
+            args.next();
+        }
+        Some("animate") => {
+            options.command = Command::Animate;
+            args.next();
+        }
+        Some("help") | Some("-h") | Some("--help") => {
+            options.command = Command::Help;
+            return Ok(options);
+        }
+        _ => {}
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" | "--all" => all = true,
+            "-d" | "--day" => {
+                let day = parse_value::<u32>(&arg, args.next())?;
+                if !options.days.contains(&day) {
+                    options.days.push(day);
+                }
+            }
+            "-p" | "--part" => {
+                let part = parse_value::<u32>(&arg, args.next())?;
+                if part != 1 && part != 2 {
+                    return Err(format!("Invalid part '{}', expected 1 or 2", part));
+                }
+                options.part = Some(part);
+            }
+            "-i" | "--input" => {
+                let input = args.next().ok_or(format!("Missing value for '{}'", arg))?;
+                options.input = Some(if input == "-" { Input::Stdin } else { Input::File(PathBuf::from(input)) });
+            }
+            "--answers" => {
+                let answers = args.next().ok_or(format!("Missing value for '{}'", arg))?;
+                options.answers = Some(PathBuf::from(answers));
+            }
+            "-f" | "--format" => options.format = parse_value::<Format>(&arg, args.next())?,
+            "-o" | "--output" => {
+                let output = args.next().ok_or(format!("Missing value for '{}'", arg))?;
+                options.output = Some(PathBuf::from(output));
+            }
+            "-v" | "--visualize" => options.visualize = true,
+            "--image" => {
+                let image = args.next().ok_or(format!("Missing value for '{}'", arg))?;
+                options.image = Some(PathBuf::from(image));
+            }
+            "--image-format" => {
+                options.image_format = parse_value::<ImageFormat>(&arg, args.next())?;
+                image_options = true;
+            }
+            "--cell-size" => {
+                let cell_size = parse_value::<usize>(&arg, args.next())?;
+                if cell_size == 0 {
+                    return Err(format!("Invalid value '{}' for '{}', expected at least 1", cell_size, arg));
+                }
+                options.cell_size = cell_size;
+                image_options = true;
+            }
+            "--palette" => {
+                options.palette = parse_value::<Palette>(&arg, args.next())?;
+                image_options = true;
+            }
+            "--frame-step" => {
+                let step = parse_value::<usize>(&arg, args.next())?;
+                if step == 0 {
+                    return Err(format!("Invalid value '{}' for '{}', expected at least 1", step, arg));
+                }
+                options.animation.step = step;
+                animation_options = true;
+            }
+            "--candidates" => {
+                options.animation.candidates = true;
+                animation_options = true;
+            }
+            "-t" | "--time" => options.time = true,
+            "-r" | "--repeat" => {
+                let repeat = parse_value::<u32>(&arg, args.next())?;
+                if repeat == 0 {
+                    return Err(format!("Invalid value '{}' for '{}', expected at least 1", repeat, arg));
+                }
+                options.repeat = repeat;
+                options.time = true;
+            }
+            "-j" | "--jobs" => options.jobs = parse_value::<usize>(&arg, args.next())?,
+            "-h" | "--help" => options.command = Command::Help,
+            _ => return Err(format!("Unknown argument '{}'", arg)),
+        }
+    }
+
+    if all && !options.days.is_empty() {
+        return Err("'--all' cannot be combined with '--day'".to_string());
+    }
+    if options.answers.is_some() && options.command != Command::Verify {
+        return Err("'--answers' can be used only with 'verify'".to_string());
+    }
+    if options.format != Format::Markdown && options.command != Command::Run {
+        return Err("'--format' can be used only with 'run'".to_string());
+    }
+    if options.visualize && (options.command != Command::Run || options.format != Format::Markdown) {
+        return Err("'--visualize' can be used only with 'run' and the markdown format".to_string());
+    }
+    if options.image.is_some() && options.command != Command::Run && options.command != Command::Animate {
+        return Err("'--image' can be used only with 'run' and 'animate'".to_string());
+    }
+    if animation_options && options.command != Command::Animate {
+        return Err("'--frame-step' and '--candidates' can be used only with 'animate'".to_string());
+    }
+    if options.command == Command::Animate && (options.days.len() != 1 || options.part.is_some() || options.image.is_none()) {
+        return Err("'animate' requires exactly one '--day' and '--image'".to_string());
+    }
+    if image_options && options.image.is_none() {
+        return Err("'--image-format', '--cell-size' and '--palette' require '--image'".to_string());
+    }
+    if options.output.is_some() && options.command != Command::Readme {
+        return Err("'--output' can be used only with 'readme'".to_string());
+    }
+    if options.command == Command::Readme && (!options.days.is_empty() || options.part.is_some()) {
+        return Err("'readme' always runs all days and parts".to_string());
+    }
+    if options.input.is_some() && options.days.len() != 1 {
+        return Err("'--input' requires exactly one '--day'".to_string());
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Options, String> {
+        parse_args(args.split_whitespace().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args_default() {
+        let options = parse("").unwrap();
+        assert_eq!(options.command, Command::Run);
+        assert!(options.days.is_empty());
+        assert_eq!(options.part, None);
+        assert_eq!(options.input, None);
+
+        assert_eq!(parse("run --all").unwrap(), options);
+    }
+
+    #[test]
+    fn test_parse_args_day_part_input() {
+        let options = parse("run --day 7 --part 2 --input path/to/file").unwrap();
+        assert_eq!(options.days, vec![7]);
+        assert_eq!(options.part, Some(2));
+        assert_eq!(options.input, Some(Input::File(PathBuf::from("path/to/file"))));
+
+        let options = parse("-d 3 -i -").unwrap();
+        assert_eq!(options.days, vec![3]);
+        assert_eq!(options.input, Some(Input::Stdin));
+
+        let options = parse("--day 1 --day 12 --day 1").unwrap();
+        assert_eq!(options.days, vec![1, 12]);
+    }
+
+    #[test]
+    fn test_parse_args_verify() {
+        let options = parse("verify").unwrap();
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(options.answers, None);
+
+        let options = parse("verify --day 9 --answers answers.txt").unwrap();
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(options.days, vec![9]);
+        assert_eq!(options.answers, Some(PathBuf::from("answers.txt")));
+
+        assert!(parse("run --answers answers.txt").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_time() {
+        let options = parse("").unwrap();
+        assert!(!options.time);
+        assert_eq!(options.repeat, 1);
+
+        let options = parse("--time").unwrap();
+        assert!(options.time);
+        assert_eq!(options.repeat, 1);
+
+        let options = parse("-r 10").unwrap();
+        assert!(options.time);
+        assert_eq!(options.repeat, 10);
+
+        assert!(parse("--repeat 0").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_format() {
+        assert_eq!(parse("").unwrap().format, Format::Markdown);
+        assert_eq!(parse("--format json").unwrap().format, Format::Json);
+        assert_eq!(parse("run -f csv").unwrap().format, Format::Csv);
+        assert!(parse("--format xml").is_err());
+        assert!(parse("verify --format json").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_readme() {
+        let options = parse("readme").unwrap();
+        assert_eq!(options.command, Command::Readme);
+        assert_eq!(options.output, None);
+        assert!(!options.time);
+
+        let options = parse("readme --time --output docs/README.md").unwrap();
+        assert!(options.time);
+        assert_eq!(options.output, Some(PathBuf::from("docs/README.md")));
+
+        assert!(parse("run --output README.md").is_err());
+        assert!(parse("readme --day 3").is_err());
+        assert!(parse("readme --part 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_visualize() {
+        assert!(!parse("").unwrap().visualize);
+        assert!(parse("--day 6 --visualize").unwrap().visualize);
+        assert!(parse("run -v").unwrap().visualize);
+        assert!(parse("verify --visualize").is_err());
+        assert!(parse("--visualize --format json").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_image() {
+        let options = parse("-d 12 --image pictures").unwrap();
+        assert_eq!(options.image, Some(PathBuf::from("pictures")));
+        assert_eq!(options.image_format, ImageFormat::Png);
+        assert_eq!(options.cell_size, 4);
+        assert_eq!(options.palette, Palette::DARK);
+
+        let options = parse("--image . --image-format ppm --cell-size 1 --palette light --format json").unwrap();
+        assert_eq!(options.image_format, ImageFormat::Ppm);
+        assert_eq!(options.cell_size, 1);
+        assert_eq!(options.palette, Palette::LIGHT);
+
+        assert!(parse("--image").is_err());
+        assert!(parse("--image . --image-format gif").is_err());
+        assert!(parse("--image . --cell-size 0").is_err());
+        assert!(parse("--image . --palette blue").is_err());
+        assert!(parse("--cell-size 2").is_err());
+        assert!(parse("verify --image .").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_animate() {
+        let options = parse("animate -d 6 --image frames").unwrap();
+        assert_eq!(options.command, Command::Animate);
+        assert_eq!(options.animation, AnimationOptions::default());
+
+        let options = parse("animate -d 6 --image frames --frame-step 10 --candidates --cell-size 2").unwrap();
+        assert_eq!(options.animation, AnimationOptions { step: 10, candidates: true });
+        assert_eq!(options.cell_size, 2);
+
+        assert!(parse("animate --image frames").is_err());
+        assert!(parse("animate -d 6 -d 7 --image frames").is_err());
+        assert!(parse("animate -d 6").is_err());
+        assert!(parse("animate -d 6 -p 1 --image frames").is_err());
+        assert!(parse("animate -d 6 --image frames --frame-step 0").is_err());
+        assert!(parse("-d 6 --image frames --candidates").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_jobs() {
+        assert_eq!(parse("").unwrap().jobs, 1);
+        assert_eq!(parse("--jobs 8").unwrap().jobs, 8);
+        assert_eq!(parse("verify -j 0").unwrap().jobs, 0);
+        assert!(parse("--jobs -1").is_err());
+        assert!(parse("--jobs").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_help() {
+        assert_eq!(parse("help").unwrap().command, Command::Help);
+        assert_eq!(parse("run --help").unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse("--day").is_err());
+        assert!(parse("--day seven").is_err());
+        assert!(parse("--part 3").is_err());
+        assert!(parse("--all --day 2").is_err());
+        assert!(parse("--input file.txt").is_err());
+        assert!(parse("--day 1 --day 2 --input file.txt").is_err());
+        assert!(parse("--unknown").is_err());
+    }
+}
@@ -1,207 +1,273 @@
-use advent::char_map::CharMap;
-use advent::{day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_10, day_11, day_12};
-use std::fs::File;
-use std::{fs, io};
-
-fn main() -> io::Result<()> {
-    println!("# Advent Of Code 2021 results");
-
-    // Day 01
-    {
-        let input = "inputs\\day_01.txt";
-        let file = match File::open(&input) {
-            Ok(file) => file,
-            Err(e) => {
-                eprintln!("Error: Failed to open file '{}': {}", input, e);
-                return Err(e);
-            }
-        };
-
-        let reader = io::BufReader::new(file);
-        let (array1, array2) = day_01::parse_numbers(reader);
-
-        let total_distance = day_01::sum_distances(&array1, &array2);
-        println!("Day 01: The total distance is **{}**  ", total_distance);
-
-        let total_similarity = day_01::sum_similarities(&array1, &array2);
-        println!("Day 01: The similarity score is **{}**  ", total_similarity);
+mod cli;
+mod pool;
+mod readme;
+mod report;
+mod timing;
+
+use advent::answers::{self, Answers};
+use advent::image::Image;
+use advent::input;
+use advent::render::{Canvas, Mode};
+use advent::solution::{self, Solution};
+use cli::{Command, Input, Options};
+use report::{DayResult, PartResult, Reporter};
+use std::fs::{self, File};
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
+use std::{env, io};
+
+fn read_input(day: u32, source: &Option<Input>) -> Result<String, String> {
+    match source {
+        Some(Input::Stdin) => io::read_to_string(io::stdin()).map_err(|e| format!("Failed to read stdin: {}", e)),
+        Some(Input::File(path)) => fs::read_to_string(path).map_err(|e| format!("Failed to read file '{}': {}", path.display(), e)),
+        None => {
+            let path = input::input_path(day);
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))
+        }
     }
+}
 
-    // Day 02
-    {
-        let input = "inputs\\day_02.txt";
-        let file = match File::open(&input) {
-            Ok(file) => file,
-            Err(e) => {
-                eprintln!("Error: Failed to open file '{}': {}", input, e);
-                return Err(e);
-            }
-        };
-
-        let reader = io::BufReader::new(file);
-        let reports = day_02::parse_reports(reader);
-
-        let safe_reports = day_02::count_safe_reports(&reports);
-        println!("Day 02: The number of safe reports is **{}**  ", safe_reports);
-
-        let safe_reports_with_tolerance = day_02::count_safe_reports_with_tolerance(&reports);
-        println!("Day 02: The number of safe reports with tolerance is **{}**  ", safe_reports_with_tolerance);
+/// Returns the name used to report errors in the input.
+fn input_name(day: u32, source: &Option<Input>) -> String {
+    match source {
+        Some(Input::Stdin) => "<stdin>".to_string(),
+        Some(Input::File(path)) => path.display().to_string(),
+        None => input::input_file_name(day),
     }
+}
 
-    // Day 03
-    {
-        let input = "inputs\\day_03.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_03.txt'");
-
-        let sum = day_03::parse_instructions_no_regex_and_sum(&input);
-        println!("Day 03: The sum of multiplication of instructions is **{}**  ", sum);
-
-        let sum_with_do = day_03::parse_instructions_with_do_no_regex_and_sum(&input);
-        println!("Day 03: The sum of multiplication of instructions of just enabled multiplications is **{}**  ", sum_with_do);
+fn select_solutions(options: &Options) -> Result<Vec<Box<dyn Solution>>, String> {
+    if options.days.is_empty() {
+        return Ok(solution::solutions());
     }
 
-    // Day 04
-    {
-        let input = "inputs\\day_04.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_04.txt'");
-
-        let word_search = day_04::WordSearch::new(input);
-
-        let xmas_count = word_search.count_xmas_check_xs();
-        println!("Day 04: The word XMAS appears **{}** times  ", xmas_count);
-
-        let mas_diagonal_count = word_search.count_mas_diagonal_check_mask();
-        println!("Day 04: The word MAS appears diagonally **{}** times  ", mas_diagonal_count);
+    let mut solutions = Vec::new();
+    for &day in &options.days {
+        match solution::find_solution(day) {
+            Some(solution) => solutions.push(solution),
+            None => return Err(format!("Day {} is not implemented", day)),
+        }
     }
+    Ok(solutions)
+}
 
-    // Day 05
-    {
-        let input = "inputs\\day_05.txt";
-        let file = match File::open(&input) {
-            Ok(file) => file,
-            Err(e) => {
-                eprintln!("Error: Failed to open file '{}': {}", input, e);
-                return Err(e);
-            }
-        };
-
-        let reader = io::BufReader::new(file);
-        let (page_ordering_rules, pages_to_produce) = day_05::parse_pages(reader);
+/// Reads and parses the input of the solution, then solves the selected parts.
+/// Parsing and each part are repeated as requested and the median time is reported.
+/// When running with more than one job, both parts are solved at the same time.
+fn solve(solution: &mut dyn Solution, options: &Options) -> Result<DayResult, String> {
+    let day = solution.day();
+    let input = read_input(day, &options.input)?;
+
+    let (parsed, parse_time) = timing::measure(options.repeat, || solution.parse(&input));
+    parsed.map_err(|e| format!("{}:{}", input_name(day, &options.input), e))?;
+
+    let solution = &*solution;
+    let (part_one, part_two) = pool::join(
+        options.jobs != 1 && options.part.is_none(),
+        || options.part.is_none_or(|part| part == 1).then(|| timing::measure(options.repeat, || solution.part_one())),
+        || options.part.is_none_or(|part| part == 2).then(|| timing::measure(options.repeat, || solution.part_two())),
+    );
+
+    let mut parts = Vec::new();
+    if let Some((answer, time)) = part_one {
+        parts.push(PartResult { part: 1, answer, time });
+    }
+    if let Some((answer, time)) = part_two {
+        parts.push(PartResult { part: 2, answer, time });
+    }
+    let canvas = if options.visualize || options.image.is_some() { solution.visualize() } else { None };
+    if let Some(canvas) = &canvas {
+        save_image(day, canvas, options)?;
+    }
+    let visualization = canvas.filter(|_| options.visualize).map(|canvas| canvas.render(render_mode()));
+    Ok(DayResult {
+        day,
+        parse_time,
+        parts,
+        visualization,
+    })
+}
 
-        let sum_of_valid_middle_pages = day_05::sum_of_valid_middle_pages(&page_ordering_rules, &pages_to_produce);
-        println!("Day 05: The sum of valid middle pages is **{}**  ", sum_of_valid_middle_pages);
+/// Saves the canvas as `day_DD.<ext>` in the image directory, if one was given.
+fn save_image(day: u32, canvas: &Canvas, options: &Options) -> Result<(), String> {
+    match &options.image {
+        Some(dir) => write_image(&dir.join(format!("day_{:02}.{}", day, options.image_format.extension())), canvas, options),
+        None => Ok(()),
+    }
+}
 
-        let mut pages_to_produce = pages_to_produce;
-        day_05::correct_pages_to_produce(&page_ordering_rules, &mut pages_to_produce);
+fn write_image(path: &Path, canvas: &Canvas, options: &Options) -> Result<(), String> {
+    let image = Image::from_canvas(canvas, options.cell_size, &options.palette);
+    let file = File::create(path).map_err(|e| format!("Failed to create file '{}': {}", path.display(), e))?;
+    let mut out = BufWriter::new(file);
+    image
+        .write(options.image_format, &mut out)
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("Failed to write file '{}': {}", path.display(), e))
+}
 
-        // After correction, all pages are valid
-        let sum_of_all_middle_pages = day_05::sum_of_all_middle_pages(&pages_to_produce);
-        println!("Day 05: The sum of corrected middle pages is **{}**  ", sum_of_all_middle_pages - sum_of_valid_middle_pages);
+/// Colours are used only on terminals and can be disabled by the `NO_COLOR` environment variable.
+fn render_mode() -> Mode {
+    if io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) {
+        Mode::Ansi
+    } else {
+        Mode::Plain
     }
+}
 
-    // Day 06
-    {
-        let input = "inputs\\day_06.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_06.txt'");
+/// Solves the days on `options.jobs` workers. The results are in the order of the solutions.
+fn solve_all(solutions: Vec<Box<dyn Solution>>, options: &Options) -> Vec<Result<DayResult, String>> {
+    pool::map_ordered(solutions, pool::worker_count(options.jobs), |mut solution| solve(solution.as_mut(), options))
+}
 
-        let mut guard_map = CharMap::new(&input);
-        let mut guard_map_to_block = guard_map.clone();
+fn run(solutions: Vec<Box<dyn Solution>>, options: &Options) -> Result<(), String> {
+    let start = Instant::now();
+    let mut reporter = Reporter::new(options.format, io::stdout().lock());
+    let write_error = |e: io::Error| format!("Failed to write the results: {}", e);
+
+    reporter.begin().map_err(write_error)?;
+    let mut results = Vec::new();
+    for result in solve_all(solutions, options) {
+        let result = result?;
+        reporter.day(&result).map_err(write_error)?;
+        results.push(result);
+    }
+    reporter.end(&results, start.elapsed(), options.time).map_err(write_error)?;
 
-        let dinstinct_positions = day_06::move_guard(&mut guard_map);
-        println!("Day 06: The guard visited **{}** distinct positions  ", dinstinct_positions);
+    Ok(())
+}
 
-        let obstruction_positions = day_06::block_guard_assign_map(&mut guard_map_to_block);
-        println!("Day 06: The guard can be looped with **{}** obstruction positions  ", obstruction_positions);
+/// Saves the frames of the animation as `day_DD_NNNNN.<ext>` in the image directory.
+fn animate(solutions: Vec<Box<dyn Solution>>, options: &Options) -> Result<(), String> {
+    let dir = options.image.as_ref().ok_or("Missing image directory")?;
+    for mut solution in solutions {
+        let day = solution.day();
+        let input = read_input(day, &options.input)?;
+        solution.parse(&input).map_err(|e| format!("{}:{}", input_name(day, &options.input), e))?;
+
+        let frames = solution.animate(options.animation).ok_or(format!("Day {} does not support animations", day))?;
+        let mut count = 0;
+        for (index, frame) in frames.enumerate() {
+            let path = dir.join(format!("day_{:02}_{:05}.{}", day, index, options.image_format.extension()));
+            write_image(&path, &frame, options)?;
+            count += 1;
+        }
+        println!("Saved {} frames of day {} to '{}'", count, day, dir.display());
     }
+    Ok(())
+}
 
-    // Day 07
-    {
-        let input = "inputs\\day_07.txt";
-        let file = match File::open(&input) {
-            Ok(file) => file,
+fn read_answers(options: &Options) -> Result<Answers, String> {
+    let path = options.answers.clone().unwrap_or_else(input::answers_path);
+    let file = File::open(&path).map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
+    answers::parse_answers(io::BufReader::new(file)).map_err(|e| format!("{}:{}", path.display(), e))
+}
+
+/// Solves the selected days and compares the results with the known answers.
+/// Returns the number of failed parts.
+fn verify(solutions: Vec<Box<dyn Solution>>, options: &Options) -> Result<usize, String> {
+    let start = Instant::now();
+    let known_answers = read_answers(options)?;
+
+    let days: Vec<u32> = solutions.iter().map(|s| s.day()).collect();
+    let mut results = Vec::new();
+    let mut passed = 0;
+    let mut failed = 0;
+    for (day, result) in days.into_iter().zip(solve_all(solutions, options)) {
+        let result = match result {
+            Ok(result) => result,
             Err(e) => {
-                eprintln!("Error: Failed to open file '{}': {}", input, e);
-                return Err(e);
+                println!("Day {:02}: FAIL, {}", day, e);
+                failed += 1;
+                continue;
             }
         };
 
-        let reader = io::BufReader::new(file);
-        let equations = day_07::parse_equations(reader);
-
-        let sum = day_07::sum_can_calibrate_values(&equations);
-        println!("Day 07: The total calibration result is **{}**  ", sum);
-
-        let sum = day_07::sum_can_calibrate_values_concat(&equations);
-        println!("Day 07: The total calibration result with concatenation is **{}**  ", sum);
-    }
-
-    // Day 08
-    {
-        let input = "inputs\\day_08.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_08.txt'");
-
-        let antennas_map = CharMap::new(&input);
-        let antennas = day_08::gather_antennas(&antennas_map);
-
-        let antinodes = day_08::find_antinodes_sort_dedup(&antennas, &antennas_map);
-        println!("Day 08: The map contains **{}** unique antinode locations  ", antinodes.len());
-
-        let antinodes_in_line = day_08::find_antinodes_in_line_sort_dedup(&antennas, &antennas_map);
-        println!("Day 08: The map contains **{}** unique antinode locations in line  ", antinodes_in_line.len());
+        for PartResult { part, answer, .. } in &result.parts {
+            match known_answers.get(&(day, *part)) {
+                Some(expected) if *expected == answer.value => {
+                    println!("Day {:02} part {}: PASS", day, part);
+                    passed += 1;
+                }
+                Some(expected) => {
+                    println!("Day {:02} part {}: FAIL, expected {}, got {}", day, part, expected, answer.value);
+                    failed += 1;
+                }
+                None => {
+                    println!("Day {:02} part {}: FAIL, no known answer, got {}", day, part, answer.value);
+                    failed += 1;
+                }
+            }
+        }
+        results.push(result);
     }
 
-    // Day 09
-    {
-        let input = "inputs\\day_09.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_09.txt'");
-        let input = input.chars().collect();
-
-        let checksum = day_09::calculate_filesystem_checksum(&input);
-        println!("Day 09: The checksum of the filesystem is **{}**  ", checksum);
-
-        let checksum = day_09::calculate_filesystem_checksum_v2_optimized(&input);
-        println!("Day 09: After moving whole files the checksum of the filesystem is **{}**  ", checksum);
+    println!("{} passed, {} failed", passed, failed);
+    if options.time {
+        report::write_timings(&mut io::stdout().lock(), &results, start.elapsed()).map_err(|e| format!("Failed to write the timings: {}", e))?;
     }
+    Ok(failed)
+}
 
-    // Day 10
-    {
-        let input = "inputs\\day_10.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_10.txt'");
-
-        let topographic_map = CharMap::new(&input);
+/// Solves all days and writes the results into the marked section of the README.
+fn update_readme(solutions: Vec<Box<dyn Solution>>, options: &Options) -> Result<(), String> {
+    let path = options.output.clone().unwrap_or_else(readme::readme_path);
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))?;
 
-        let sum_of_tailhead_scores = day_10::calculate_sum_of_tailhead_scores(&topographic_map);
-        println!("Day 10: The sum of tailhead scores is **{}**  ", sum_of_tailhead_scores);
+    let results = solve_all(solutions, options).into_iter().collect::<Result<Vec<_>, _>>()?;
 
-        let sum_of_tailhead_ratings = day_10::calculate_sum_of_tailhead_ratings(&topographic_map);
-        println!("Day 10: The sum of tailhead ratings is **{}**  ", sum_of_tailhead_ratings);
+    let section = readme::render_results(&results, options.time);
+    let updated = readme::replace_section(&content, &section).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if updated == content {
+        println!("'{}' is up to date", path.display());
+    } else {
+        fs::write(&path, updated).map_err(|e| format!("Failed to write file '{}': {}", path.display(), e))?;
+        println!("Updated '{}'", path.display());
     }
+    Ok(())
+}
 
-    // Day 11
-    {
-        let input = "inputs\\day_11.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_11.txt'");
-
-        let stones = day_11::parse_stones(&input);
-
-        let num_of_stones = day_11::calculate_number_of_stones_after_blinks_cached(&stones, 25);
-        println!("Day 11: The number of stones after 25 blinks is **{}**  ", num_of_stones);
-
-        let num_of_stones = day_11::calculate_number_of_stones_after_blinks_cached(&stones, 75);
-        println!("Day 11: The number of stones after 75 blinks is **{}**  ", num_of_stones);
+fn main() -> ExitCode {
+    let options = match cli::parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if options.command == Command::Help {
+        print!("{}", cli::USAGE);
+        return ExitCode::SUCCESS;
     }
 
-    // Day 12
-    {
-        let input = "inputs\\day_12.txt";
-        let input = fs::read_to_string(input).expect("Error: Failed to read file 'inputs\\day_12.txt'");
-
-        let garden = CharMap::new(&input);
-
-        let prices = day_12::calculate_fencing_price(&garden);
-        println!("Day 12: The fencing price is **{}** ", prices.0);
-        println!("Day 12: The new fencing price is **{}** ", prices.1);
+    let solutions = match select_solutions(&options) {
+        Ok(solutions) => solutions,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match options.command {
+        Command::Run => run(solutions, &options),
+        Command::Verify => match verify(solutions, &options) {
+            Ok(0) => Ok(()),
+            Ok(_) => return ExitCode::FAILURE,
+            Err(e) => Err(e),
+        },
+        Command::Readme => update_readme(solutions, &options),
+        Command::Animate => animate(solutions, &options),
+        Command::Help => unreachable!(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
     }
-
-    Ok(())
 }
@@ -0,0 +1,88 @@
+﻿use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Returns the number of workers to use. Zero means one worker per available core.
+pub fn worker_count(jobs: usize) -> usize {
+    if jobs == 0 {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    } else {
+        jobs
+    }
+}
+
+/// Applies the function to all items on up to `jobs` worker threads.
+/// The results are returned in the order of the items, regardless of which one finished first.
+pub fn map_ordered<T: Send, R: Send, F: Fn(T) -> R + Sync>(items: Vec<T>, jobs: usize, f: F) -> Vec<R> {
+    let workers = jobs.min(items.len());
+    if workers <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let items: Vec<Mutex<Option<T>>> = items.into_iter().map(|item| Mutex::new(Some(item))).collect();
+    let results: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= items.len() {
+                    break;
+                }
+                let item = items[index].lock().unwrap().take().unwrap();
+                let result = f(item);
+                *results[index].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    results.into_iter().map(|result| result.into_inner().unwrap().unwrap()).collect()
+}
+
+/// Runs both functions, on two threads when `parallel` is set.
+pub fn join<A: Send, B: Send, FA: FnOnce() -> A + Send, FB: FnOnce() -> B + Send>(parallel: bool, fa: FA, fb: FB) -> (A, B) {
+    if !parallel {
+        return (fa(), fb());
+    }
+
+    thread::scope(|scope| {
+        let b = scope.spawn(fb);
+        let a = fa();
+        (a, b.join().unwrap())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_worker_count() {
+        assert_eq!(worker_count(3), 3);
+        assert!(worker_count(0) >= 1);
+    }
+
+    #[test]
+    fn test_map_ordered() {
+        let items: Vec<u64> = (0..20).collect();
+        let expected: Vec<u64> = items.iter().map(|x| x * x).collect();
+
+        for jobs in [1, 2, 4, 32] {
+            let results = map_ordered(items.clone(), jobs, |x| {
+                // Later items finish first to make sure the order does not depend on timing.
+                thread::sleep(Duration::from_micros(20 - x));
+                x * x
+            });
+            assert_eq!(results, expected);
+        }
+        assert!(map_ordered(Vec::<u64>::new(), 4, |x| x).is_empty());
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!(join(false, || 1, || "two"), (1, "two"));
+        assert_eq!(join(true, || 1, || "two"), (1, "two"));
+    }
+}
@@ -0,0 +1,95 @@
+﻿use crate::report::DayResult;
+use crate::timing;
+use std::path::{Path, PathBuf};
+
+/// Marks the beginning of the generated results section in the README.
+pub const BEGIN_MARKER: &str = "<!-- results:begin -->";
+/// Marks the end of the generated results section in the README.
+pub const END_MARKER: &str = "<!-- results:end -->";
+
+/// Returns the path to `README.md` in the crate root.
+pub fn readme_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("README.md")
+}
+
+/// Renders the results as a Markdown table, optionally with the time of each part.
+pub fn render_results(results: &[DayResult], time: bool) -> String {
+    let mut section = String::new();
+    if time {
+        section.push_str("| Day | Result | Time |\n|----:|:-------|-----:|\n");
+    } else {
+        section.push_str("| Day | Result |\n|----:|:-------|\n");
+    }
+
+    for result in results {
+        for part in &result.parts {
+            let answer = &part.answer;
+            let description = answer.describe(&format!("**{}**", answer.value)).replace('|', "\\|");
+            section.push_str(&format!("| {:02} | {} |", result.day, description));
+            if time {
+                section.push_str(&format!(" {} |", timing::format_duration(part.time)));
+            }
+            section.push('\n');
+        }
+    }
+    section
+}
+
+/// Replaces everything between the markers with the section, keeping the rest of the README untouched.
+pub fn replace_section(readme: &str, section: &str) -> Result<String, String> {
+    let begin = readme.find(BEGIN_MARKER).ok_or(format!("Missing '{}' marker", BEGIN_MARKER))?;
+    let content_begin = begin + BEGIN_MARKER.len();
+    let end = readme[content_begin..]
+        .find(END_MARKER)
+        .ok_or(format!("Missing '{}' marker after '{}'", END_MARKER, BEGIN_MARKER))?
+        + content_begin;
+
+    Ok(format!("{}\n\n{}\n{}", &readme[..content_begin], section, &readme[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::tests;
+    use advent::solution::Answer;
+    use std::time::Duration;
+
+    /// The shared results, with a pipe to escape in the second answer.
+    fn results() -> Vec<DayResult> {
+        let mut results = tests::results();
+        results[0].parts[1].answer = Answer::new("The word X|MAS appears {} times", 9);
+        results[0].parts[1].time = Duration::from_micros(300);
+        results
+    }
+
+    #[test]
+    fn test_render_results() {
+        assert_eq!(
+            render_results(&results(), false),
+            "| Day | Result |\n|----:|:-------|\n| 04 | The word XMAS appears **18** times |\n| 04 | The word X\\|MAS appears **9** times |\n"
+        );
+        assert_eq!(
+            render_results(&results(), true),
+            "| Day | Result | Time |\n|----:|:-------|-----:|\n| 04 | The word XMAS appears **18** times | 200 ns |\n| 04 | The word X\\|MAS appears **9** times | 300.000 µs |\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_section() {
+        let readme = "# Title\n\nProse.\n\n<!-- results:begin -->\nold\n<!-- results:end -->\n\nMore prose.\n";
+        assert_eq!(
+            replace_section(readme, "new\n").unwrap(),
+            "# Title\n\nProse.\n\n<!-- results:begin -->\n\nnew\n\n<!-- results:end -->\n\nMore prose.\n"
+        );
+
+        let updated = replace_section(readme, "new\n").unwrap();
+        assert_eq!(replace_section(&updated, "new\n").unwrap(), updated);
+    }
+
+    #[test]
+    fn test_replace_section_errors() {
+        assert!(replace_section("# Title\n", "new\n").is_err());
+        assert!(replace_section("<!-- results:begin -->\nold\n", "new\n").is_err());
+        assert!(replace_section("<!-- results:end -->\n<!-- results:begin -->\n", "new\n").is_err());
+    }
+}
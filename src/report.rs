@@ -0,0 +1,259 @@
+﻿use crate::timing;
+use advent::solution::Answer;
+use std::io::{self, Write};
+use std::time::Duration;
+
+pub struct PartResult {
+    pub part: u32,
+    pub answer: Answer,
+    pub time: Duration,
+}
+
+pub struct DayResult {
+    pub day: u32,
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+    /// Annotated map of the input, when requested and supported by the day.
+    pub visualization: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Markdown,
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Format::Markdown),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Writes the results in the selected format, one day at a time.
+pub struct Reporter<W: Write> {
+    format: Format,
+    out: W,
+    records: usize,
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(format: Format, out: W) -> Reporter<W> {
+        Reporter { format, out, records: 0 }
+    }
+
+    pub fn begin(&mut self) -> io::Result<()> {
+        match self.format {
+            Format::Markdown => writeln!(self.out, "# Advent Of Code 2024 results"),
+            Format::Json => write!(self.out, "["),
+            Format::Csv => writeln!(self.out, "day,part,description,answer,parse_time_ns,time_ns"),
+        }
+    }
+
+    pub fn day(&mut self, result: &DayResult) -> io::Result<()> {
+        for part in &result.parts {
+            let answer = &part.answer;
+            match self.format {
+                Format::Markdown => writeln!(self.out, "Day {:02}: {}  ", result.day, answer.describe(&format!("**{}**", answer.value)))?,
+                Format::Json => write!(
+                    self.out,
+                    "{}\n  {{\"day\": {}, \"part\": {}, \"description\": {}, \"answer\": {}, \"parse_time_ns\": {}, \"time_ns\": {}}}",
+                    if self.records == 0 { "" } else { "," },
+                    result.day,
+                    part.part,
+                    escape_json(&answer.describe(&answer.value)),
+                    escape_json(&answer.value),
+                    result.parse_time.as_nanos(),
+                    part.time.as_nanos()
+                )?,
+                Format::Csv => writeln!(
+                    self.out,
+                    "{},{},{},{},{},{}",
+                    result.day,
+                    part.part,
+                    escape_csv(&answer.describe(&answer.value)),
+                    escape_csv(&answer.value),
+                    result.parse_time.as_nanos(),
+                    part.time.as_nanos()
+                )?,
+            }
+            self.records += 1;
+        }
+        // The map is printed only by the Markdown report, the other formats stay machine-readable.
+        if let (Format::Markdown, Some(visualization)) = (self.format, &result.visualization) {
+            writeln!(self.out, "\n{}", visualization)?;
+        }
+        Ok(())
+    }
+
+    /// Finishes the report. The Markdown report ends with the timing summary when `time` is set.
+    pub fn end(&mut self, results: &[DayResult], wall_time: Duration, time: bool) -> io::Result<()> {
+        match self.format {
+            Format::Markdown if time => write_timings(&mut self.out, results, wall_time),
+            Format::Markdown => Ok(()),
+            Format::Json if self.records == 0 => writeln!(self.out, "]"),
+            Format::Json => writeln!(self.out, "\n]"),
+            Format::Csv => Ok(()),
+        }
+    }
+}
+
+/// Writes a Markdown table with the parse and part times of each day.
+pub fn write_timings<W: Write>(out: &mut W, results: &[DayResult], wall_time: Duration) -> io::Result<()> {
+    fn part_time(result: &DayResult, part: u32) -> String {
+        match result.parts.iter().find(|p| p.part == part) {
+            Some(p) => timing::format_duration(p.time),
+            None => "-".to_string(),
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "| Day | Parse | Part 1 | Part 2 | Total |")?;
+    writeln!(out, "|----:|------:|-------:|-------:|------:|")?;
+    let mut total = Duration::ZERO;
+    for result in results {
+        let day_total = result.parse_time + result.parts.iter().map(|p| p.time).sum::<Duration>();
+        total += day_total;
+        writeln!(
+            out,
+            "| {:02} | {} | {} | {} | {} |",
+            result.day,
+            timing::format_duration(result.parse_time),
+            part_time(result, 1),
+            part_time(result, 2),
+            timing::format_duration(day_total)
+        )?;
+    }
+    writeln!(out, "| **Total** | | | | **{}** |", timing::format_duration(total))?;
+    writeln!(out)?;
+    writeln!(out, "Total wall time: **{}**  ", timing::format_duration(wall_time))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Results of a single day with both parts, shared with the tests of the other outputs.
+    pub(crate) fn results() -> Vec<DayResult> {
+        vec![DayResult {
+            day: 4,
+            parse_time: Duration::from_nanos(100),
+            parts: vec![
+                PartResult {
+                    part: 1,
+                    answer: Answer::new("The word XMAS appears {} times", 18),
+                    time: Duration::from_nanos(200),
+                },
+                PartResult {
+                    part: 2,
+                    answer: Answer::new("The word \"MAS\", diagonally, {} times", 9),
+                    time: Duration::from_nanos(300),
+                },
+            ],
+            visualization: None,
+        }]
+    }
+
+    fn report(format: Format) -> String {
+        let results = results();
+        let mut out = Vec::new();
+        let mut reporter = Reporter::new(format, &mut out);
+        reporter.begin().unwrap();
+        for result in &results {
+            reporter.day(result).unwrap();
+        }
+        reporter.end(&results, Duration::from_millis(1), false).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("markdown".parse(), Ok(Format::Markdown));
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!("xml".parse::<Format>(), Err(()));
+    }
+
+    #[test]
+    fn test_report_markdown() {
+        assert_eq!(
+            report(Format::Markdown),
+            "# Advent Of Code 2024 results\nDay 04: The word XMAS appears **18** times  \nDay 04: The word \"MAS\", diagonally, **9** times  \n"
+        );
+    }
+
+    #[test]
+    fn test_report_markdown_visualization() {
+        let mut results = results();
+        results[0].visualization = Some("XMAS\n".to_string());
+        let mut out = Vec::new();
+        let mut reporter = Reporter::new(Format::Markdown, &mut out);
+        reporter.day(&results[0]).unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("9** times  \n\nXMAS\n\n"));
+
+        let mut out = Vec::new();
+        Reporter::new(Format::Csv, &mut out).day(&results[0]).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("XMAS\n"));
+    }
+
+    #[test]
+    fn test_report_json() {
+        assert_eq!(
+            report(Format::Json),
+            r#"[
+  {"day": 4, "part": 1, "description": "The word XMAS appears 18 times", "answer": "18", "parse_time_ns": 100, "time_ns": 200},
+  {"day": 4, "part": 2, "description": "The word \"MAS\", diagonally, 9 times", "answer": "9", "parse_time_ns": 100, "time_ns": 300}
+]
+"#
+        );
+    }
+
+    #[test]
+    fn test_report_csv() {
+        assert_eq!(
+            report(Format::Csv),
+            r#"day,part,description,answer,parse_time_ns,time_ns
+4,1,The word XMAS appears 18 times,18,100,200
+4,2,"The word ""MAS"", diagonally, 9 times",9,100,300
+"#
+        );
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+    }
+}
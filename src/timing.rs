@@ -0,0 +1,80 @@
+﻿use std::time::{Duration, Instant};
+
+/// Runs the function `repeat` times and returns the last result with the median duration.
+pub fn measure<T, F: FnMut() -> T>(repeat: u32, mut f: F) -> (T, Duration) {
+    assert!(repeat > 0);
+    let mut samples = Vec::with_capacity(repeat as usize);
+    let mut result = None;
+    for _ in 0..repeat {
+        let start = Instant::now();
+        result = Some(f());
+        samples.push(start.elapsed());
+    }
+    (result.unwrap(), median(&mut samples))
+}
+
+/// Returns the median of the samples. For an even number of samples it is the mean of the two middle ones.
+pub fn median(samples: &mut [Duration]) -> Duration {
+    if samples.is_empty() {
+        return Duration::ZERO;
+    }
+    samples.sort();
+    let middle = samples.len() / 2;
+    if samples.len() % 2 == 1 {
+        samples[middle]
+    } else {
+        (samples[middle - 1] + samples[middle]) / 2
+    }
+}
+
+/// Formats the duration with a unit fitting its magnitude, e.g. `12.345 ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{} ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.3} µs", nanos as f64 / 1_000.0)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.3} ms", nanos as f64 / 1_000_000.0)
+    } else {
+        format!("{:.3} s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut []), Duration::ZERO);
+        assert_eq!(median(&mut [Duration::from_millis(5)]), Duration::from_millis(5));
+        assert_eq!(
+            median(&mut [Duration::from_millis(9), Duration::from_millis(1), Duration::from_millis(4)]),
+            Duration::from_millis(4)
+        );
+        assert_eq!(
+            median(&mut [Duration::from_millis(9), Duration::from_millis(1), Duration::from_millis(4), Duration::from_millis(2)]),
+            Duration::from_millis(3)
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let (result, _) = measure(5, || {
+            calls += 1;
+            calls * 2
+        });
+        assert_eq!(calls, 5);
+        assert_eq!(result, 10);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999 ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_500)), "1.500 µs");
+        assert_eq!(format_duration(Duration::from_micros(12_345)), "12.345 ms");
+        assert_eq!(format_duration(Duration::from_millis(2_500)), "2.500 s");
+    }
+}
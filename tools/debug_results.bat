@@ -1,6 +0,0 @@
-pushd "%~dp0"
-
-cd ..
-call target\debug\AdventOfCode2024.exe > README.md
-
-popd
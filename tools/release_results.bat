@@ -1,6 +0,0 @@
-pushd "%~dp0"
-
-cd ..
-call target\release\AdventOfCode2024.exe > README.md
-
-popd